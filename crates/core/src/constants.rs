@@ -7,8 +7,31 @@ lazy_static! {
     pub static ref NOT_BECAUSE_META: i32 = -2;
     pub static ref DEFAULT_EXPORT: &'static str = "default";
     pub static ref DEFAULT_EXPORT_LEN: i32 = 7;
+    pub static ref NAMESPACE: &'static str = "*";
+    pub static ref EQUAL: &'static str = "=";
     pub static ref BRACKET_LEFT: &'static str = "(";
     pub static ref SEMI: &'static str = ";";
     pub static ref SEMI_UNICODE: u16 = SEMI.encode_utf16().next().unwrap();
     pub static ref EXPORT_LEN: i32 = 6;
+    pub static ref AWAIT: &'static str = "await";
+    pub static ref AWAIT_LEN: i32 = 5;
+    pub static ref REFERENCE: &'static str = "<reference";
+    pub static ref REFERENCE_PATH: &'static str = "path=";
+    pub static ref REFERENCE_TYPES: &'static str = "types=";
+    pub static ref IMPORT: &'static str = "import";
+    pub static ref FROM: &'static str = "from";
+    pub static ref URL: &'static str = "URL";
+    pub static ref META_URL: &'static str = "url";
+    pub static ref WORKER: &'static str = "Worker";
+    pub static ref SHARED_WORKER: &'static str = "SharedWorker";
+    pub static ref GLOB: &'static str = "glob";
+    pub static ref CONTEXT: &'static str = "context";
+    pub static ref REQUIRE_CONTEXT_OPTIONS: Vec<&'static str> = vec!["recursive", "regExp", "mode"];
+    pub static ref HOT: &'static str = "hot";
+    pub static ref REQUIRE: &'static str = "require";
+    pub static ref RESOLVE: &'static str = "resolve";
+    pub static ref MODULE: &'static str = "module";
+    pub static ref EXPORTS: &'static str = "exports";
+    pub static ref OBJECT: &'static str = "Object";
+    pub static ref DEFINE_PROPERTY: &'static str = "defineProperty";
 }
@@ -37,15 +37,308 @@ pub enum ImportType {
             import.defer('module')
     "]
     DynamicDeferPhase = 7,
+    #[doc = "
+        A CommonJS require call
+            require('module')
+    "]
+    Require = 8,
+    #[doc = "
+        A CommonJS require resolve call
+            require.resolve('module')
+    "]
+    RequireResolve = 9,
+    #[doc = "
+        A TypeScript import equals require
+            import a = require('module')
+    "]
+    ImportEquals = 10,
+    #[doc = "
+        A TypeScript import type
+            type A = typeof import('module')
+    "]
+    TypeImport = 11,
+    #[doc = "
+        A triple-slash path reference
+            /// <reference path=\"module\" />
+    "]
+    ReferencePath = 12,
+    #[doc = "
+        A triple-slash types reference
+            /// <reference types=\"module\" />
+    "]
+    ReferenceTypes = 13,
+    #[doc = "
+        An asset URL relative to the module
+            new URL('./asset', import.meta.url)
+    "]
+    UrlAsset = 14,
+    #[doc = "
+        A worker URL relative to the module
+            new Worker(new URL('./worker', import.meta.url))
+    "]
+    Worker = 15,
+    #[doc = "
+        A Vite glob import
+            import.meta.glob('./dir/*.js')
+    "]
+    ImportMetaGlob = 16,
+    #[doc = "
+        A webpack require context
+            require.context('./dir', true, /\\.js$/)
+    "]
+    RequireContext = 17,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ExportType {
+    #[doc = "
+        A CommonJS module exports assignment
+            module.exports = a
+            module.exports = { a, b }
+    "]
+    ModuleExports = 1,
+    #[doc = "
+        A CommonJS named exports assignment
+            exports.a = 1
+            module.exports.a = 1
+    "]
+    Exports = 2,
+    #[doc = "
+        A CommonJS exports property definition
+            Object.defineProperty(exports, 'a', { value: 1 })
+    "]
+    DefineProperty = 3,
+    #[doc = "
+        A TypeScript export assignment
+            export = a
+    "]
+    ExportAssignment = 4,
+    #[doc = "
+        A TypeScript UMD namespace export
+            export as namespace a
+    "]
+    NamespaceExport = 5,
+    #[doc = "
+        A local export
+            export const a = 1
+            export { a as b }
+    "]
+    Local = 6,
+    #[doc = "
+        A named re-export
+            export { a as b } from 'module'
+    "]
+    ReexportNamed = 7,
+    #[doc = "
+        A namespace re-export
+            export * as a from 'module'
+    "]
+    ReexportNamespace = 8,
+    #[doc = "
+        A star re-export
+            export * from 'module'
+    "]
+    Star = 9,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ImportBindingType {
+    #[doc = "
+        A default import
+            import a from 'module'
+    "]
+    Default = 1,
+    #[doc = "
+        A named import
+            import { a, b as c } from 'module'
+    "]
+    Named = 2,
+    #[doc = "
+        A namespace import
+            import * as a from 'module'
+    "]
+    Namespace = 3,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum JsDocImportType {
+    #[doc = "
+        A JSDoc import tag
+            /** @import { A } from 'module' */
+    "]
+    Import = 1,
+    #[doc = "
+        A JSDoc import type
+            /** @type {import('module').A} */
+    "]
+    TypeImport = 2,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum HmrType {
+    #[doc = "
+        Accept updates of self or dependencies
+            import.meta.hot.accept('./dep', cb)
+    "]
+    Accept = 1,
+    #[doc = "
+        Clean up before the module is replaced
+            import.meta.hot.dispose(cb)
+    "]
+    Dispose = 2,
+    #[doc = "
+        Reject updates of self or dependencies
+            module.hot.decline('./dep')
+    "]
+    Decline = 3,
+    #[doc = "
+        Propagate the update to importers
+            import.meta.hot.invalidate()
+    "]
+    Invalidate = 4,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ExportKind {
+    #[doc = "
+        A const declaration
+            export const a = 1
+    "]
+    Const = 1,
+    #[doc = "
+        A let declaration
+            export let a = 1
+    "]
+    Let = 2,
+    #[doc = "
+        A var declaration
+            export var a = 1
+    "]
+    Var = 3,
+    #[doc = "
+        A function declaration
+            export function a() {}
+    "]
+    Function = 4,
+    #[doc = "
+        An async function declaration
+            export async function a() {}
+    "]
+    AsyncFunction = 5,
+    #[doc = "
+        A generator function declaration
+            export function* a() {}
+            export async function* a() {}
+    "]
+    Generator = 6,
+    #[doc = "
+        A class declaration
+            export class A {}
+    "]
+    Class = 7,
+    #[doc = "
+        A TypeScript enum declaration
+            export enum A {}
+    "]
+    Enum = 8,
+    #[doc = "
+        A TypeScript namespace declaration
+            export namespace A {}
+    "]
+    Namespace = 9,
+    #[doc = "
+        A re-binding of an existing name
+            export default a
+            export { a as b }
+    "]
+    Binding = 10,
+    #[doc = "
+        A default export of an expression
+            export default 1 + 1
+    "]
+    Expression = 11,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DiagnosticSeverity {
+    Error = 1,
+    Warning = 2,
+    Note = 3,
+    Help = 4,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ModuleKind {
+    #[doc = " ES module, e.g. `import` / `export` / `import.meta` "]
+    Esm = 1,
+    #[doc = " CommonJS module, e.g. top level `require()` / `module.exports` "]
+    Cjs = 2,
+    #[doc = " Both ES module and CommonJS syntax "]
+    Mixed = 3,
+    #[doc = " Neither ES module nor CommonJS syntax "]
+    Script = 4,
+    #[doc = " Failed to parse "]
+    Unknown = 5,
+}
+
+#[derive(Debug, PartialEq)]
+// napi already derives `Clone` and `Copy` for enums, same condition as `multi_env!`
+#[cfg_attr(not(all(feature = "node", not(feature = "wasm"))), derive(Clone, Copy))]
+pub enum Lang {
+    Js = 1,
+    Jsx = 2,
+    Ts = 3,
+    Tsx = 4,
+    #[doc = "
+        TypeScript declaration file
+            declare const a: string
+    "]
+    Dts = 5,
 }
 
 }
 
 multi_env! {
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct IConfig {
     pub input: Vec<ParseOptions>,
+    #[doc = " Report syntax errors on each `ParseResult` instead of failing the whole batch "]
+    pub collect_errors: Option<bool>,
+    #[doc = " Return partial imports / exports of broken files, their errors are always reported "]
+    pub error_recovery: Option<bool>,
+    #[doc = " Parser features of all files "]
+    pub parser: Option<ParserOptions>,
+    #[doc = " Also report CommonJS `require` / `exports` "]
+    pub cjs: Option<bool>,
+    #[doc = " Resolve `n` of dynamic imports with static template literal or string concatenation "]
+    pub resolve_static_specifier: Option<bool>,
+    #[doc = " Report glob pattern of dynamic imports with variables, e.g. import(`./locales/${lang}.json`) "]
+    pub dynamic_import_pattern: Option<bool>,
+    #[doc = " Report imported / local names of static imports in `bindings` "]
+    pub import_bindings: Option<bool>,
+    #[doc = " Also report type-only imports / exports, marked by `is_type` "]
+    pub include_types: Option<bool>,
+    #[doc = " Also report TypeScript `import('x')` types and triple-slash references "]
+    pub type_references: Option<bool>,
+    #[doc = " Report JSDoc `@import` tags and `import('x')` types in `jsdoc_imports` "]
+    pub jsdoc: Option<bool>,
+    #[doc = " Report bundler magic comments of dynamic imports, e.g. `webpackChunkName` "]
+    pub magic_comments: Option<bool>,
+    #[doc = " Also report `new URL('./a', import.meta.url)` assets and workers "]
+    pub assets: Option<bool>,
+    #[doc = " Also report `import.meta.glob()` / `require.context()` glob imports "]
+    pub glob_imports: Option<bool>,
+    #[doc = " Report accessed properties of `import.meta` in `meta`, e.g. `import.meta.env.MODE` "]
+    pub import_meta_members: Option<bool>,
+    #[doc = " Report `import.meta.hot` / `module.hot` API calls in `hmr` "]
+    pub hmr: Option<bool>,
+    #[doc = " Export every bound name of destructuring, e.g. `export const { a: { b }, ...c } = {}` "]
+    pub full_destructuring: Option<bool>,
+    #[doc = " Link re-exports to their imports by `import_index`, and report kind of ES module exports in `t` "]
+    pub link_reexports: Option<bool>,
+    #[doc = " Report declaration kind of exports in `kind` and `mutable` "]
+    pub export_kinds: Option<bool>,
 }
 
 #[derive(Debug, Default)]
@@ -53,10 +346,40 @@ pub struct IResult {
     pub output: Vec<ParseResult>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ParseOptions {
     pub filename: String,
     pub code: String,
+    #[doc = " Syntax of code, overrides the detection by `filename` extension "]
+    pub lang: Option<Lang>,
+    #[doc = " Parser features of this file, overrides `IConfig.parser` "]
+    pub parser: Option<ParserOptions>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ParserOptions {
+    #[doc = " Enable decorators, default `true` for TypeScript and `false` for JavaScript "]
+    pub decorators: Option<bool>,
+    #[doc = " Allow decorators before `export` (JavaScript only) "]
+    pub decorators_before_export: Option<bool>,
+    #[doc = " Support `export v from 'mod'`, default `true` (JavaScript only) "]
+    pub export_default_from: Option<bool>,
+    #[doc = " Support function bind expression `a::b` (JavaScript only) "]
+    pub fn_bind: Option<bool>,
+    #[doc = " Allow `super` outside of method (JavaScript only) "]
+    pub allow_super_outside_method: Option<bool>,
+    #[doc = " Allow `return` outside of function, e.g. CommonJS scripts (JavaScript only) "]
+    pub allow_return_outside_function: Option<bool>,
+    #[doc = " Support `accessor` class fields (JavaScript only) "]
+    pub auto_accessors: Option<bool>,
+    #[doc = " Support `using` declarations (JavaScript only) "]
+    pub explicit_resource_management: Option<bool>,
+    #[doc = " Skip early errors (TypeScript only) "]
+    pub no_early_errors: Option<bool>,
+    #[doc = " Disallow `<X> y` type assertions which are ambiguous with JSX (TypeScript only) "]
+    pub disallow_ambiguous_jsx_like: Option<bool>,
+    #[doc = " ECMAScript version of syntax, e.g. `es2015`, default `esnext` "]
+    pub target: Option<String>,
 }
 
 #[derive(Debug)]
@@ -66,6 +389,134 @@ pub struct ParseResult {
     pub exports: Vec<ExportSpecifier>,
     pub facade: bool,
     pub has_module_syntax: bool,
+    #[doc = " Module system of this file "]
+    pub module_kind: ModuleKind,
+    #[doc = " Whether top level `await` / `for await` is used "]
+    pub has_top_level_await: bool,
+    #[doc = " `export * from` / `export * as a from` statements "]
+    pub star_exports: Vec<StarExport>,
+    #[doc = " The first top level `await` keyword "]
+    pub top_level_await: Option<Range>,
+    #[doc = " Syntax error of this file, only reported when `collect_errors` is enabled "]
+    pub error: Option<ParseError>,
+    #[doc = " All diagnostics of this file, only reported when `collect_errors` is enabled "]
+    pub diagnostics: Option<Vec<Diagnostic>>,
+    #[doc = " Imports within JSDoc comments, only reported when `jsdoc` is enabled "]
+    pub jsdoc_imports: Option<Vec<JsDocImport>>,
+    #[doc = " HMR API calls, only reported when `hmr` is enabled "]
+    pub hmr: Option<Vec<HmrCall>>,
+}
+
+#[derive(Debug)]
+pub struct HmrCall {
+    #[doc = " Type of HMR API "]
+    pub t: HmrType,
+    #[doc = " Dependencies, empty means self, e.g. `import.meta.hot.accept()` "]
+    pub deps: Vec<HmrDependency>,
+    #[doc = " Call start index "]
+    pub ss: i32,
+    #[doc = " Call end index "]
+    pub se: i32,
+}
+
+#[derive(Debug)]
+pub struct HmrDependency {
+    #[doc = " Source name "]
+    pub n: String,
+    #[doc = " Source start index "]
+    pub s: i32,
+    #[doc = " Source end index "]
+    pub e: i32,
+}
+
+#[derive(Debug)]
+pub struct StarExport {
+    #[doc = " Source name "]
+    pub n: String,
+    #[doc = " Source start index "]
+    pub s: i32,
+    #[doc = " Source end index "]
+    pub e: i32,
+    #[doc = " Export start index "]
+    pub ss: i32,
+    #[doc = " Export end index "]
+    pub se: i32,
+    #[doc = " Namespace name of `export * as a from`, `None` of `export * from` "]
+    pub namespace: Option<String>,
+    #[doc = " Whether this is `export type * from`, only reported with `include_types` "]
+    pub is_type: Option<bool>,
+}
+
+#[derive(Debug)]
+pub struct JsDocImport {
+    #[doc = " Source name "]
+    pub n: String,
+    #[doc = " Source start index within the comment "]
+    pub s: i32,
+    #[doc = " Source end index within the comment "]
+    pub e: i32,
+    #[doc = " Comment start index "]
+    pub ss: i32,
+    #[doc = " Comment end index "]
+    pub se: i32,
+    #[doc = " Type of JSDoc import "]
+    pub t: JsDocImportType,
+}
+
+#[derive(Debug)]
+pub struct Range {
+    #[doc = " Start index "]
+    pub s: i32,
+    #[doc = " End index "]
+    pub e: i32,
+}
+
+#[derive(Debug)]
+pub struct ParseError {
+    #[doc = " Error message "]
+    pub message: String,
+    #[doc = " Filename of the broken file "]
+    pub filename: String,
+    #[doc = " Error line (1-based) "]
+    pub line: i32,
+    #[doc = " Error column (0-based) "]
+    pub column: i32,
+    #[doc = " Error start index "]
+    pub s: i32,
+    #[doc = " Error end index "]
+    pub e: i32,
+}
+
+#[derive(Debug)]
+pub struct Diagnostic {
+    #[doc = " Severity of diagnostic "]
+    pub severity: DiagnosticSeverity,
+    #[doc = " Diagnostic code "]
+    pub code: Option<String>,
+    #[doc = " Diagnostic message "]
+    pub message: String,
+    #[doc = " Diagnostic start index "]
+    pub s: i32,
+    #[doc = " Diagnostic end index "]
+    pub e: i32,
+    #[doc = " Diagnostic line (1-based) "]
+    pub line: i32,
+    #[doc = " Diagnostic column (0-based) "]
+    pub column: i32,
+    #[doc = " Labeled spans related to this diagnostic "]
+    pub labels: Vec<DiagnosticLabel>,
+}
+
+#[derive(Debug)]
+pub struct DiagnosticLabel {
+    #[doc = " Label message "]
+    pub message: Option<String>,
+    #[doc = " Label start index "]
+    pub s: i32,
+    #[doc = " Label end index "]
+    pub e: i32,
+    #[doc = " Whether this is the primary span of the diagnostic "]
+    pub primary: bool,
 }
 
 #[derive(Debug)]
@@ -88,6 +539,82 @@ pub struct ImportSpecifier {
     pub t: ImportType,
     #[doc = " Import attributes "]
     pub at: Option<Vec<Vec<String>>>,
+    #[doc = " Pattern of dynamic import with variables "]
+    pub pattern: Option<DynamicImportPattern>,
+    #[doc = " Bindings of static import "]
+    pub bindings: Option<Vec<ImportBinding>>,
+    #[doc = " Whether this is a type-only import, only reported with `include_types` "]
+    pub is_type: Option<bool>,
+    #[doc = " Magic comments of dynamic import "]
+    pub magic_comments: Option<Vec<MagicComment>>,
+    #[doc = " Patterns and options of glob import "]
+    pub glob: Option<GlobImport>,
+    #[doc = " Accessed properties of `import.meta` "]
+    pub meta: Option<ImportMetaMember>,
+}
+
+#[derive(Debug)]
+pub struct ImportMetaMember {
+    #[doc = " Accessed property names, e.g. `['env', 'MODE']` "]
+    pub props: Vec<String>,
+    #[doc = " Member chain start index "]
+    pub s: i32,
+    #[doc = " Member chain end index "]
+    pub e: i32,
+    #[doc = " Whether the member chain is called, e.g. `import.meta.resolve('a')` "]
+    pub call: bool,
+}
+
+#[derive(Debug)]
+pub struct GlobImport {
+    #[doc = " Glob patterns, or the directory of `require.context()` "]
+    pub patterns: Vec<String>,
+    #[doc = " Literal options, quotes of string are removed, e.g. `[['eager', 'true']]` "]
+    pub options: Vec<Vec<String>>,
+    #[doc = " Options start index, -1 if not written "]
+    pub os: i32,
+    #[doc = " Options end index, -1 if not written "]
+    pub oe: i32,
+}
+
+#[derive(Debug)]
+pub struct MagicComment {
+    #[doc = " Key, e.g. `webpackChunkName` / `@vite-ignore` "]
+    pub key: String,
+    #[doc = " Value, quotes of string are removed, e.g. `chunk` / `true` "]
+    pub value: Option<String>,
+    #[doc = " Comment start index "]
+    pub s: i32,
+    #[doc = " Comment end index "]
+    pub e: i32,
+}
+
+#[derive(Debug)]
+pub struct ImportBinding {
+    #[doc = " Imported name, `default` of default import and `*` of namespace import "]
+    pub n: String,
+    #[doc = " Local name "]
+    pub ln: String,
+    #[doc = " Type of binding "]
+    pub t: ImportBindingType,
+    #[doc = " Imported name start index, -1 if not written "]
+    pub s: i32,
+    #[doc = " Imported name end index, -1 if not written "]
+    pub e: i32,
+    #[doc = " Local name start index "]
+    pub ls: i32,
+    #[doc = " Local name end index "]
+    pub le: i32,
+}
+
+#[derive(Debug)]
+pub struct DynamicImportPattern {
+    #[doc = " Glob pattern, e.g. `./locales/*.json` "]
+    pub glob: String,
+    #[doc = " Static parts around the placeholders, e.g. `./locales/` and `.json` "]
+    pub parts: Vec<String>,
+    #[doc = " Start / end indexes of the variable expressions "]
+    pub placeholders: Vec<Range>,
 }
 
 #[derive(Debug)]
@@ -104,6 +631,16 @@ pub struct ExportSpecifier {
     pub ls: i32,
     #[doc = " Export origin name end index "]
     pub le: i32,
+    #[doc = " Type of export, CommonJS / TypeScript exports, or ES module exports with `link_reexports` "]
+    pub t: Option<ExportType>,
+    #[doc = " Whether this is a type-only export, only reported with `include_types` "]
+    pub is_type: Option<bool>,
+    #[doc = " Index of the import of re-export in `imports`, only reported with `link_reexports` "]
+    pub import_index: Option<i32>,
+    #[doc = " Declaration kind of export, only reported with `export_kinds` "]
+    pub kind: Option<ExportKind>,
+    #[doc = " Whether the export is a live binding that can be reassigned (`let` / `var`), only reported with `export_kinds`, `None` if unknown, e.g. re-exports "]
+    pub mutable: Option<bool>,
 }
 
 }
@@ -1,42 +1,92 @@
+use std::cmp;
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::path::PathBuf;
 
 use anyhow::{anyhow, Context};
 use swc_common::{
-    comments::SingleThreadedComments, errors::Handler, sync::Lrc, FileName, SourceMap, GLOBALS,
+    comments::{Comments, SingleThreadedComments},
+    errors::{Diagnostic as SwcDiagnostic, DiagnosticId, Handler, Level},
+    input::StringInput,
+    sync::Lrc,
+    BytePos, FileName, SourceFile, SourceMap, Span, Spanned, DUMMY_SP, GLOBALS,
 };
 use swc_config::is_module::IsModule;
 use swc_ecmascript::{
-    ast::EsVersion,
-    parser::{EsSyntax, Syntax, TsSyntax},
+    ast::{self, EsVersion, Program},
+    parser::{error::Error as SwcParserError, lexer::Lexer, EsSyntax, Parser, Syntax, TsSyntax},
     visit::VisitMutWith,
 };
-use swc_error_reporters::handler::{try_with_handler, HandlerOpts};
+use swc_error_reporters::{
+    handler::{try_with_handler, HandlerOpts, ThreadSafetyDiagnostics},
+    ErrorEmitter, TWithDiagnosticArray,
+};
 
 use crate::constants::*;
-use crate::decl::{ParseOptions, ParseResult};
+use crate::decl::{
+    Diagnostic, DiagnosticLabel, DiagnosticSeverity, IConfig, Lang, ModuleKind, ParseError,
+    ParseOptions, ParseResult, ParserOptions,
+};
 use crate::visitor::ImportExportVisitor;
 
-pub fn parse_code(opts: &ParseOptions) -> Result<ParseResult, anyhow::Error> {
-    let ParseOptions { filename, code } = opts;
+pub fn parse_code(opts: &ParseOptions, config: &IConfig) -> Result<ParseResult, anyhow::Error> {
+    let ParseOptions {
+        filename,
+        code,
+        lang,
+        parser,
+    } = opts;
     let file_info = parse_filename(filename.as_str());
     let FileInfo {
-        is_jsx,
-        is_typescript,
+        mut is_jsx,
+        mut is_typescript,
+        mut is_dts,
         filename_path_buf,
         ..
     } = file_info;
 
+    if let Some(lang) = lang {
+        (is_typescript, is_jsx, is_dts) = match lang {
+            Lang::Js => (false, false, false),
+            Lang::Jsx => (false, true, false),
+            Lang::Ts => (true, false, false),
+            Lang::Tsx => (true, true, false),
+            Lang::Dts => (true, false, true),
+        };
+    }
+
+    // file options first, then global options
+    let pick = |get: fn(&ParserOptions) -> Option<bool>| {
+        parser
+            .as_ref()
+            .and_then(get)
+            .or_else(|| config.parser.as_ref().and_then(get))
+    };
+    let target = parser
+        .as_ref()
+        .and_then(|p| p.target.as_ref())
+        .or_else(|| config.parser.as_ref().and_then(|p| p.target.as_ref()));
     let syntax = if is_typescript {
         Syntax::Typescript(TsSyntax {
             tsx: is_jsx,
-            decorators: true,
-            ..Default::default()
+            dts: is_dts,
+            decorators: pick(|p| p.decorators).unwrap_or(true),
+            no_early_errors: pick(|p| p.no_early_errors).unwrap_or_default(),
+            disallow_ambiguous_jsx_like: pick(|p| p.disallow_ambiguous_jsx_like)
+                .unwrap_or_default(),
         })
     } else {
         Syntax::Es(EsSyntax {
             jsx: is_jsx,
-            export_default_from: true,
+            decorators: pick(|p| p.decorators).unwrap_or_default(),
+            decorators_before_export: pick(|p| p.decorators_before_export).unwrap_or_default(),
+            export_default_from: pick(|p| p.export_default_from).unwrap_or(true),
+            fn_bind: pick(|p| p.fn_bind).unwrap_or_default(),
+            allow_super_outside_method: pick(|p| p.allow_super_outside_method).unwrap_or_default(),
+            allow_return_outside_function: pick(|p| p.allow_return_outside_function)
+                .unwrap_or_default(),
+            auto_accessors: pick(|p| p.auto_accessors).unwrap_or_default(),
+            explicit_resource_management: pick(|p| p.explicit_resource_management)
+                .unwrap_or_default(),
             ..Default::default()
         })
     };
@@ -47,20 +97,61 @@ pub fn parse_code(opts: &ParseOptions) -> Result<ParseResult, anyhow::Error> {
         code.clone(),
     );
     let comments = SingleThreadedComments::default();
+    let error_recovery = config.error_recovery.unwrap_or(false);
+    // recovery mode always reports errors of the broken file
+    let collect_errors = config.collect_errors.unwrap_or(false) || error_recovery;
+    let mut recovered: Vec<Diagnostic> = vec![];
 
-    try_with(source_map.clone(), false, |handler| {
-        let mut module = swc_compiler_base::parse_js(
+    let target = match target.map(|target| {
+        serde_json::from_value::<EsVersion>(target.as_str().into())
+            .map_err(|err| anyhow!("invalid parser target of {filename}: {err}"))
+    }) {
+        Some(Ok(target)) => target,
+        Some(Err(err)) if collect_errors => {
+            // keep going, report the error on this file only
+            let error = ParseError {
+                message: err.to_string(),
+                filename: filename.to_owned(),
+                line: *NOT,
+                column: *NOT,
+                s: *NOT,
+                e: *NOT,
+            };
+            return Ok(to_error_result(filename, error, vec![]));
+        }
+        Some(Err(err)) => return Err(err),
+        None => EsVersion::EsNext,
+    };
+
+    let result = try_with(source_map.clone(), false, |handler| {
+        let mut module = if error_recovery {
+            let (module, diagnostics) =
+                parse_with_recovery(&source_map, &source_file, syntax, target, &comments);
+            recovered = diagnostics;
+            Program::Module(module)
+        } else {
+            swc_compiler_base::parse_js(
+                source_map.clone(),
+                source_file.clone(),
+                &handler,
+                target,
+                syntax,
+                IsModule::Bool(true),
+                Some(&comments),
+            )
+            .context("failed to parse code")?
+        };
+
+        let mut visitor = ImportExportVisitor::new(
+            code.clone(),
             source_map.clone(),
             source_file.clone(),
-            &handler,
-            EsVersion::EsNext,
-            syntax,
-            IsModule::Bool(true),
-            Some(&comments),
-        )
-        .context("failed to parse code")?;
-
-        let mut visitor = ImportExportVisitor::new(code.clone(), source_map, source_file);
+            &comments,
+            config,
+        );
+        visitor.parse_references(&module);
+        visitor.parse_local_bindings(&module);
+        visitor.parse_jsdoc();
         module.visit_mut_with(&mut visitor);
 
         Ok(ParseResult {
@@ -69,8 +160,277 @@ pub fn parse_code(opts: &ParseOptions) -> Result<ParseResult, anyhow::Error> {
             exports: visitor.exports,
             facade: visitor.facade,
             has_module_syntax: visitor.has_module_syntax,
+            module_kind: visitor.module_kind,
+            star_exports: visitor.star_exports,
+            has_top_level_await: visitor.top_level_await.is_some(),
+            top_level_await: visitor.top_level_await,
+            error: None,
+            diagnostics: None,
+            jsdoc_imports: visitor.jsdoc_imports,
+            hmr: visitor.hmr,
         })
-    })
+    });
+
+    match result {
+        Ok(mut result) => {
+            if collect_errors {
+                result.error = recovered
+                    .first()
+                    .map(|diagnostic| to_parse_error(filename, diagnostic));
+                result.diagnostics = Some(recovered);
+            }
+            Ok(result)
+        }
+        Err(err) => {
+            if !collect_errors {
+                return Err(err.to_pretty_error());
+            }
+            // keep going, report the error on this file only
+            let diagnostics = err
+                .diagnostics()
+                .iter()
+                .map(|diagnostic| to_diagnostic(&source_map, &source_file, diagnostic))
+                .collect::<Vec<Diagnostic>>();
+            let error = match diagnostics.first() {
+                Some(diagnostic) => to_parse_error(filename, diagnostic),
+                // e.g. panic
+                None => ParseError {
+                    message: err.to_string(),
+                    filename: filename.to_owned(),
+                    line: *NOT,
+                    column: *NOT,
+                    s: *NOT,
+                    e: *NOT,
+                },
+            };
+            Ok(to_error_result(filename, error, diagnostics))
+        }
+    }
+}
+
+fn to_error_result(filename: &str, error: ParseError, diagnostics: Vec<Diagnostic>) -> ParseResult {
+    ParseResult {
+        filename: filename.to_owned(),
+        imports: vec![],
+        exports: vec![],
+        facade: false,
+        has_module_syntax: false,
+        module_kind: ModuleKind::Unknown,
+        star_exports: vec![],
+        has_top_level_await: false,
+        top_level_await: None,
+        error: Some(error),
+        diagnostics: Some(diagnostics),
+        jsdoc_imports: None,
+        hmr: None,
+    }
+}
+
+fn to_parse_error(filename: &str, diagnostic: &Diagnostic) -> ParseError {
+    ParseError {
+        message: diagnostic.message.clone(),
+        filename: filename.to_owned(),
+        line: diagnostic.line,
+        column: diagnostic.column,
+        s: diagnostic.s,
+        e: diagnostic.e,
+    }
+}
+
+fn to_diagnostic(
+    cm: &SourceMap,
+    source_file: &SourceFile,
+    diagnostic: &SwcDiagnostic,
+) -> Diagnostic {
+    let severity = match diagnostic.level {
+        Level::Warning => DiagnosticSeverity::Warning,
+        Level::Note => DiagnosticSeverity::Note,
+        Level::Help => DiagnosticSeverity::Help,
+        _ => DiagnosticSeverity::Error,
+    };
+    let code = diagnostic.code.as_ref().map(|code| match code {
+        DiagnosticId::Error(code) => code.to_owned(),
+        DiagnosticId::Lint(code) => code.to_owned(),
+    });
+    let (s, e, line, column) = get_position(cm, source_file, diagnostic.span.primary_span());
+
+    let mut labels = diagnostic
+        .span
+        .span_labels()
+        .into_iter()
+        .map(|label| {
+            let (s, e, ..) = get_position(cm, source_file, Some(label.span));
+            DiagnosticLabel {
+                message: label.label,
+                s,
+                e,
+                primary: label.is_primary,
+            }
+        })
+        .collect::<Vec<DiagnosticLabel>>();
+    // e.g. note: ...
+    diagnostic.children.iter().for_each(|child| {
+        if let Some(span) = child.span.primary_span() {
+            let (s, e, ..) = get_position(cm, source_file, Some(span));
+            labels.push(DiagnosticLabel {
+                message: Some(child.message()),
+                s,
+                e,
+                primary: false,
+            })
+        }
+    });
+
+    Diagnostic {
+        severity,
+        code,
+        message: diagnostic.message(),
+        s,
+        e,
+        line,
+        column,
+        labels,
+    }
+}
+
+// (start, end, line, column)
+fn get_position(
+    cm: &SourceMap,
+    source_file: &SourceFile,
+    span: Option<Span>,
+) -> (i32, i32, i32, i32) {
+    match span {
+        Some(span) if !span.is_dummy() => {
+            let loc = cm.lookup_char_pos(span.lo);
+            let (s, e) = cm.span_to_char_offset(source_file, span);
+            (s as i32, e as i32, loc.line as i32, loc.col.0 as i32)
+        }
+        _ => (*NOT, *NOT, *NOT, *NOT),
+    }
+}
+
+// Parse as much as possible of a broken file (e.g. the user is still typing).
+// Recoverable errors keep the whole module, a fatal error cuts the code back to
+// the longest prefix which can be parsed, spans are kept relative to the full file.
+fn parse_with_recovery(
+    cm: &Lrc<SourceMap>,
+    source_file: &SourceFile,
+    syntax: Syntax,
+    target: EsVersion,
+    comments: &SingleThreadedComments,
+) -> (ast::Module, Vec<Diagnostic>) {
+    let code = source_file.src.as_str();
+    let start_pos = source_file.start_pos;
+    let parse_prefix = |end: usize| {
+        let prefix_comments = SingleThreadedComments::default();
+        let input = StringInput::new(&code[..end], start_pos, start_pos + BytePos(end as u32));
+        let lexer = Lexer::new(syntax, target, input, Some(&prefix_comments));
+        let mut parser = Parser::new_from(lexer);
+        let result = parser.parse_module();
+        (result, parser.take_errors(), prefix_comments)
+    };
+    let get_pos = |err: &SwcParserError| {
+        let pos = err.span().lo.0.saturating_sub(start_pos.0) as usize;
+        cmp::min(pos, code.len())
+    };
+
+    let (result, mut errors, mut module_comments) = parse_prefix(code.len());
+    let module = match result {
+        Ok(module) => module,
+        Err(fatal) => {
+            let fatal_pos = get_pos(&fatal);
+            errors.push(fatal);
+            let mut end = line_start(code, fatal_pos);
+            // the shortest prefix known to be broken
+            let mut broken = fatal_pos + 1;
+            let mut lines = 1;
+            let (mut module, mut prefix_comments) = loop {
+                let (result, _, prefix_comments) = parse_prefix(end);
+                match result {
+                    Ok(module) => break (module, prefix_comments),
+                    Err(_) if end == 0 => {
+                        let module = ast::Module {
+                            span: DUMMY_SP,
+                            body: vec![],
+                            shebang: None,
+                        };
+                        break (module, prefix_comments);
+                    }
+                    Err(err) => {
+                        let pos = get_pos(&err);
+                        broken = cmp::min(end, pos + 1);
+                        if pos < end {
+                            end = line_start(code, pos);
+                            lines = 1;
+                        } else {
+                            // unexpected eof, e.g. unclosed bracket, go back faster
+                            for _ in 0..lines {
+                                end = line_start(code, end.saturating_sub(1));
+                            }
+                            lines *= 2;
+                        }
+                    }
+                }
+            };
+            // we may go back too far, binary search a longer prefix
+            loop {
+                let mid = line_start(code, end + (broken - end) / 2);
+                if mid <= end {
+                    break;
+                }
+                let (result, _, mid_comments) = parse_prefix(mid);
+                match result {
+                    Ok(mid_module) => {
+                        end = mid;
+                        module = mid_module;
+                        prefix_comments = mid_comments;
+                    }
+                    Err(_) => broken = mid,
+                }
+            }
+            module_comments = prefix_comments;
+            module
+        }
+    };
+
+    let (leading, trailing) = module_comments.take_all();
+    leading
+        .take()
+        .into_iter()
+        .for_each(|(pos, list)| comments.add_leading_comments(pos, list));
+    trailing
+        .take()
+        .into_iter()
+        .for_each(|(pos, list)| comments.add_trailing_comments(pos, list));
+
+    let mut diagnostics = ThreadSafetyDiagnostics::default();
+    let handler = Handler::with_emitter(
+        true,
+        false,
+        Box::new(ErrorEmitter {
+            diagnostics: diagnostics.clone(),
+            cm: cm.clone(),
+            opts: Default::default(),
+        }),
+    );
+    errors
+        .into_iter()
+        .for_each(|err| err.into_diagnostic(&handler).emit());
+    let diagnostics = diagnostics
+        .take()
+        .iter()
+        .map(|diagnostic| to_diagnostic(cm, source_file, diagnostic))
+        .collect();
+
+    (module, diagnostics)
+}
+
+// start index of the line which contains `pos`
+fn line_start(code: &str, pos: usize) -> usize {
+    code.as_bytes()[..pos]
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+        .map_or(0, |idx| idx + 1)
 }
 
 #[derive(Debug)]
@@ -80,9 +440,12 @@ pub struct FileInfo {
     pub filename_path_buf: PathBuf,
     pub is_jsx: bool,
     pub is_typescript: bool,
+    pub is_dts: bool,
 }
 
 pub fn parse_filename(filepath: &str) -> FileInfo {
+    // strip query and hash, e.g. `/src/a.ts?v=123`
+    let filepath = filepath.split(['?', '#']).next().unwrap();
     let filename = filepath.split('/').last().unwrap();
     let extension = filename.split('.').last().unwrap();
     let is_typescript = TS_EXTS.contains(&extension);
@@ -95,6 +458,7 @@ pub fn parse_filename(filepath: &str) -> FileInfo {
         filename: filename.to_string(),
         is_jsx,
         is_typescript,
+        is_dts: false,
         filename_path_buf,
     }
 }
@@ -103,35 +467,33 @@ pub fn try_with<F, Ret>(
     cm: Lrc<SourceMap>,
     skip_filename: bool,
     op: F,
-) -> Result<Ret, anyhow::Error>
+) -> Result<Ret, TWithDiagnosticArray<anyhow::Error>>
 where
     F: FnOnce(&Handler) -> Result<Ret, anyhow::Error>,
 {
-    GLOBALS
-        .set(&Default::default(), || {
-            try_with_handler(
-                cm,
-                HandlerOpts {
-                    skip_filename,
-                    ..Default::default()
-                },
-                |handler| {
-                    let result = catch_unwind(AssertUnwindSafe(|| op(handler)));
-
-                    let p = match result {
-                        Ok(v) => return v,
-                        Err(v) => v,
-                    };
-
-                    if let Some(s) = p.downcast_ref::<String>() {
-                        Err(anyhow!("failed to handle: {}", s))
-                    } else if let Some(s) = p.downcast_ref::<&str>() {
-                        Err(anyhow!("failed to handle: {}", s))
-                    } else {
-                        Err(anyhow!("failed to handle with unknown panic message"))
-                    }
-                },
-            )
-        })
-        .map_err(|e| e.to_pretty_error())
+    GLOBALS.set(&Default::default(), || {
+        try_with_handler(
+            cm,
+            HandlerOpts {
+                skip_filename,
+                ..Default::default()
+            },
+            |handler| {
+                let result = catch_unwind(AssertUnwindSafe(|| op(handler)));
+
+                let p = match result {
+                    Ok(v) => return v,
+                    Err(v) => v,
+                };
+
+                if let Some(s) = p.downcast_ref::<String>() {
+                    Err(anyhow!("failed to handle: {}", s))
+                } else if let Some(s) = p.downcast_ref::<&str>() {
+                    Err(anyhow!("failed to handle: {}", s))
+                } else {
+                    Err(anyhow!("failed to handle with unknown panic message"))
+                }
+            },
+        )
+    })
 }
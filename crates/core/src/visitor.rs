@@ -1,15 +1,22 @@
 use std::cmp;
+use std::collections::HashMap;
 
 use swc_atoms::Wtf8Atom;
 use swc_common::{
+    comments::{CommentKind, Comments, SingleThreadedComments},
     Spanned,
-    {sync::Lrc, SourceFile, SourceMap, Span},
+    {sync::Lrc, BytePos, SourceFile, SourceMap, Span},
 };
 use swc_ecmascript::ast::{self, ImportPhase};
 use swc_ecmascript::visit::{VisitMut, VisitMutWith};
 
 use crate::constants::*;
-use crate::decl::{ExportSpecifier, ImportSpecifier, ImportType};
+use crate::decl::{
+    DynamicImportPattern, ExportKind, ExportSpecifier, ExportType, GlobImport, HmrCall,
+    HmrDependency, HmrType, IConfig, ImportBinding, ImportBindingType, ImportMetaMember,
+    ImportSpecifier, ImportType, JsDocImport, JsDocImportType, MagicComment, ModuleKind, Range,
+    StarExport,
+};
 
 pub trait Wtf8AtomExt {
     fn to_string(&self) -> String;
@@ -25,24 +32,88 @@ pub struct ImportExportVisitor {
     pub exports: Vec<ExportSpecifier>,
     pub facade: bool,
     pub has_module_syntax: bool,
+    pub has_cjs_syntax: bool,
+    pub module_kind: ModuleKind,
+    pub star_exports: Vec<StarExport>,
+    pub top_level_await: Option<Range>,
+    pub jsdoc_imports: Option<Vec<JsDocImport>>,
+    pub hmr: Option<Vec<HmrCall>>,
+
+    cjs: bool,
+    resolve_static_specifier: bool,
+    dynamic_import_pattern: bool,
+    import_bindings: bool,
+    include_types: bool,
+    type_references: bool,
+    jsdoc: bool,
+    magic_comments: bool,
+    assets: bool,
+    glob_imports: bool,
+    import_meta_members: bool,
+    full_destructuring: bool,
+    link_reexports: bool,
+    export_kinds: bool,
+    // member chain of the next visited `import.meta`
+    import_meta_member: Option<(Span, ImportMetaMember)>,
+    // `new URL()` of `new Worker()`, it's reported as worker
+    worker_url_span: Option<Span>,
+    // top level declarations, whether they can be reassigned (`let` / `var`)
+    local_bindings: HashMap<String, bool>,
+
+    // depth of function / class scopes, `0` means top level
+    function_depth: usize,
 
     code_utf16: Vec<u16>,
     source_map: Lrc<SourceMap>,
     source_file: Lrc<SourceFile>,
+    comments: SingleThreadedComments,
 }
 
 impl ImportExportVisitor {
-    pub fn new(code: String, source_map: Lrc<SourceMap>, source_file: Lrc<SourceFile>) -> Self {
+    pub fn new(
+        code: String,
+        source_map: Lrc<SourceMap>,
+        source_file: Lrc<SourceFile>,
+        comments: &SingleThreadedComments,
+        config: &IConfig,
+    ) -> Self {
         let code_utf16 = code.encode_utf16().collect();
         Self {
             imports: vec![],
             exports: vec![],
             facade: false,
             has_module_syntax: false,
+            has_cjs_syntax: false,
+            module_kind: ModuleKind::Script,
+            star_exports: vec![],
+            top_level_await: None,
+            jsdoc_imports: None,
+            hmr: config.hmr.unwrap_or(false).then(Vec::new),
+
+            cjs: config.cjs.unwrap_or(false),
+            resolve_static_specifier: config.resolve_static_specifier.unwrap_or(false),
+            dynamic_import_pattern: config.dynamic_import_pattern.unwrap_or(false),
+            import_bindings: config.import_bindings.unwrap_or(false),
+            include_types: config.include_types.unwrap_or(false),
+            type_references: config.type_references.unwrap_or(false),
+            jsdoc: config.jsdoc.unwrap_or(false),
+            magic_comments: config.magic_comments.unwrap_or(false),
+            assets: config.assets.unwrap_or(false),
+            glob_imports: config.glob_imports.unwrap_or(false),
+            import_meta_members: config.import_meta_members.unwrap_or(false),
+            full_destructuring: config.full_destructuring.unwrap_or(false),
+            link_reexports: config.link_reexports.unwrap_or(false),
+            export_kinds: config.export_kinds.unwrap_or(false),
+            import_meta_member: None,
+            worker_url_span: None,
+            local_bindings: HashMap::new(),
+
+            function_depth: 0,
 
             code_utf16,
             source_map,
             source_file,
+            comments: comments.clone(),
         }
     }
 }
@@ -51,13 +122,17 @@ impl ImportExportVisitor {
 impl ImportExportVisitor {
     fn add_import(&mut self, mut import: ImportSpecifier) {
         import.se = self.forward_until_first_not_semi_idx(import.se);
+        if self.include_types && import.is_type.is_none() {
+            import.is_type = Some(false);
+        }
         self.imports.push(import);
     }
 
     fn parse_import(&mut self, import: &ast::ImportDecl) {
         // import type { a } from 'b'
-        // import a, { type b } from 'b'
-        if import.type_only {
+        // import { type c } from 'b'
+        let is_type = is_type_import(import);
+        if is_type && !self.include_types {
             return;
         }
 
@@ -68,6 +143,7 @@ impl ImportExportVisitor {
             let src_span = self.get_real_span_without_quotes(import.src.span);
             let a = self.calc_assert(&import.with);
             let attrs = self.get_attrs(&import.with);
+            let bindings = self.get_import_bindings(import);
             self.add_import(ImportSpecifier {
                 n: Some(name),
                 s: src_span.0,
@@ -78,19 +154,16 @@ impl ImportExportVisitor {
                 a,
                 t: ImportType::Static,
                 at: attrs,
+                pattern: None,
+                bindings,
+                is_type: is_type.then_some(true),
+                magic_comments: None,
+                glob: None,
+                meta: None,
             });
             return;
         }
 
-        // import { type c } from 'b'
-        let is_all_type_import = import.specifiers.iter().all(|specifier| match specifier {
-            ast::ImportSpecifier::Named(named) => named.is_type_only,
-            _ => false,
-        });
-        if is_all_type_import {
-            return;
-        }
-
         let first_specifier = &import.specifiers[0];
         match first_specifier {
             // import a from 'bbbb'
@@ -105,6 +178,7 @@ impl ImportExportVisitor {
                 let import_span = self.get_real_span(import.span);
                 let a = self.calc_assert(&import.with);
                 let attrs = self.get_attrs(&import.with);
+                let bindings = self.get_import_bindings(import);
 
                 let t: Option<ImportType>;
 
@@ -134,18 +208,206 @@ impl ImportExportVisitor {
                         a,
                         t: t.unwrap(),
                         at: attrs,
+                        pattern: None,
+                        bindings,
+                        is_type: is_type.then_some(true),
+                        magic_comments: None,
+                        glob: None,
+                        meta: None,
                     })
                 }
             }
         }
     }
+
+    fn parse_import_equals(&mut self, import: &ast::TsImportEqualsDecl) {
+        // import type a = require('b')
+        if import.is_type_only && !self.include_types {
+            return;
+        }
+
+        // export import a = require('b')
+        if import.is_export {
+            self.add_export_from_ident(&import.id);
+        }
+
+        // import a = b.c
+        let module_ref = match &import.module_ref {
+            ast::TsModuleRef::TsExternalModuleRef(module_ref) => module_ref,
+            ast::TsModuleRef::TsEntityName(_) => return,
+        };
+        let name = module_ref.expr.value.to_string();
+        let src_span = self.get_real_span_without_quotes(module_ref.expr.span);
+        let import_span = self.get_real_span(import.span);
+        let bindings = if self.import_bindings {
+            let (ls, le) = self.get_real_span(import.id.span);
+            Some(vec![ImportBinding {
+                n: NAMESPACE.to_string(),
+                ln: import.id.sym.to_string(),
+                t: ImportBindingType::Namespace,
+                s: *NOT,
+                e: *NOT,
+                ls,
+                le,
+            }])
+        } else {
+            None
+        };
+        self.add_import(ImportSpecifier {
+            n: Some(name),
+            s: src_span.0,
+            e: src_span.1,
+            ss: import_span.0,
+            se: import_span.1,
+            d: *NOT,
+            a: *NOT,
+            t: ImportType::ImportEquals,
+            at: None,
+            pattern: None,
+            bindings,
+            is_type: import.is_type_only.then_some(true),
+            magic_comments: None,
+            glob: None,
+            meta: None,
+        });
+    }
+
+    fn get_import_bindings(&self, import: &ast::ImportDecl) -> Option<Vec<ImportBinding>> {
+        if !self.import_bindings {
+            return None;
+        }
+
+        let is_type = is_type_import(import);
+        let mut bindings = vec![];
+        for specifier in &import.specifiers {
+            match specifier {
+                // import a from 'b'
+                ast::ImportSpecifier::Default(default) => {
+                    let (ls, le) = self.get_real_span(default.local.span);
+                    bindings.push(ImportBinding {
+                        n: DEFAULT_EXPORT.to_string(),
+                        ln: default.local.sym.to_string(),
+                        t: ImportBindingType::Default,
+                        s: *NOT,
+                        e: *NOT,
+                        ls,
+                        le,
+                    });
+                }
+                // import { a, b as c, 'd' as e } from 'b'
+                ast::ImportSpecifier::Named(named) => {
+                    // import a, { type b } from 'c'
+                    if named.is_type_only && !is_type {
+                        continue;
+                    }
+                    let (ls, le) = self.get_real_span(named.local.span);
+                    let (name, (s, e)) = match &named.imported {
+                        Some(ast::ModuleExportName::Ident(ident)) => {
+                            (ident.sym.to_string(), self.get_real_span(ident.span))
+                        }
+                        Some(ast::ModuleExportName::Str(str)) => {
+                            (str.value.to_string(), self.get_real_span(str.span))
+                        }
+                        None => (named.local.sym.to_string(), (ls, le)),
+                    };
+                    bindings.push(ImportBinding {
+                        n: name,
+                        ln: named.local.sym.to_string(),
+                        t: ImportBindingType::Named,
+                        s,
+                        e,
+                        ls,
+                        le,
+                    });
+                }
+                // import * as a from 'b'
+                ast::ImportSpecifier::Namespace(namespace) => {
+                    let (ls, le) = self.get_real_span(namespace.local.span);
+                    bindings.push(ImportBinding {
+                        n: NAMESPACE.to_string(),
+                        ln: namespace.local.sym.to_string(),
+                        t: ImportBindingType::Namespace,
+                        s: *NOT,
+                        e: *NOT,
+                        ls,
+                        le,
+                    });
+                }
+            }
+        }
+        Some(bindings)
+    }
 }
 
 // export
 impl ImportExportVisitor {
-    fn add_export(&mut self, export: ExportSpecifier) {
+    fn add_export(&mut self, mut export: ExportSpecifier) {
+        if self.link_reexports && export.t.is_none() {
+            export.t = Some(ExportType::Local);
+        }
+        if self.include_types && export.is_type.is_none() {
+            export.is_type = Some(false);
+        }
         self.exports.push(export);
     }
+    fn set_export_kind(&mut self, start_index: usize, get_kind: impl Fn() -> ExportKind) {
+        if !self.export_kinds {
+            return;
+        }
+        self.exports[start_index..].iter_mut().for_each(|export| {
+            let kind = get_kind();
+            export.mutable = match kind {
+                ExportKind::Let | ExportKind::Var => Some(true),
+                // depends on the declaration of the binding
+                ExportKind::Binding => None,
+                _ => Some(false),
+            };
+            export.kind = Some(kind);
+        });
+    }
+
+    // let a = 1
+    // export { a }
+    pub fn parse_local_bindings(&mut self, program: &ast::Program) {
+        if !self.export_kinds {
+            return;
+        }
+        let ast::Program::Module(module) = program else {
+            return;
+        };
+
+        for item in &module.body {
+            let decl = match item {
+                ast::ModuleItem::Stmt(ast::Stmt::Decl(decl)) => decl,
+                ast::ModuleItem::ModuleDecl(ast::ModuleDecl::ExportDecl(export)) => &export.decl,
+                _ => continue,
+            };
+            match decl {
+                ast::Decl::Var(decl) => {
+                    let mutable = decl.kind != ast::VarDeclKind::Const;
+                    let mut names = vec![];
+                    decl.decls
+                        .iter()
+                        .for_each(|decl| get_pat_names(&decl.name, &mut names));
+                    names.into_iter().for_each(|name| {
+                        self.local_bindings.insert(name, mutable);
+                    });
+                }
+                ast::Decl::Class(decl) => {
+                    self.local_bindings
+                        .insert(decl.ident.sym.to_string(), false);
+                }
+                ast::Decl::Fn(decl) => {
+                    self.local_bindings
+                        .insert(decl.ident.sym.to_string(), false);
+                }
+                ast::Decl::TsEnum(decl) => {
+                    self.local_bindings.insert(decl.id.sym.to_string(), false);
+                }
+                _ => {}
+            }
+        }
+    }
 
     fn add_export_from_ident(&mut self, ident: &ast::Ident) {
         let name = ident.sym.to_string();
@@ -157,14 +419,51 @@ impl ImportExportVisitor {
             e: end,
             ls: start,
             le: end,
+            t: None,
+            is_type: None,
+            import_index: None,
+            kind: None,
+            mutable: None,
         })
     }
 
-    fn parse_export_spec(&mut self, specifier: &ast::ExportSpecifier) -> bool {
+    fn add_export_from_pat(&mut self, pat: &ast::Pat) {
+        match pat {
+            // a
+            ast::Pat::Ident(ident) => self.add_export_from_ident(&ident.id),
+            // [a, , b]
+            ast::Pat::Array(array) => array
+                .elems
+                .iter()
+                .flatten()
+                .for_each(|elem| self.add_export_from_pat(elem)),
+            ast::Pat::Object(obj) => obj.props.iter().for_each(|prop| match prop {
+                // { a: b }
+                ast::ObjectPatProp::KeyValue(kv) => self.add_export_from_pat(&kv.value),
+                // { a = 1 }
+                ast::ObjectPatProp::Assign(assign) => self.add_export_from_ident(&assign.key.id),
+                // { ...a }
+                ast::ObjectPatProp::Rest(rest) => self.add_export_from_pat(&rest.arg),
+            }),
+            // ...a
+            ast::Pat::Rest(rest) => self.add_export_from_pat(&rest.arg),
+            // a = 1
+            ast::Pat::Assign(assign) => self.add_export_from_pat(&assign.left),
+            ast::Pat::Expr(_) | ast::Pat::Invalid(_) => {}
+        }
+    }
+
+    fn parse_export_spec(
+        &mut self,
+        specifier: &ast::ExportSpecifier,
+        type_only: bool,
+        import_index: Option<i32>,
+    ) -> bool {
         match specifier {
             ast::ExportSpecifier::Named(named) => {
                 // skip type
-                if named.is_type_only {
+                let is_type = type_only || named.is_type_only;
+                if is_type && !self.include_types {
                     return false;
                 }
 
@@ -227,14 +526,33 @@ impl ImportExportVisitor {
                     e: end,
                     ls: ln_start,
                     le: ln_end,
+                    t: import_index.map(|_| ExportType::ReexportNamed),
+                    is_type: is_type.then_some(true),
+                    import_index,
+                    kind: None,
+                    mutable: None,
                 });
 
                 return true;
             }
             // export v from 'm'
-            // current not support
-            ast::ExportSpecifier::Default(_) => {
-                return false;
+            ast::ExportSpecifier::Default(default) => {
+                let name = default.exported.sym.to_string();
+                let (start, end) = self.get_real_span(default.exported.span);
+                self.add_export(ExportSpecifier {
+                    n: name,
+                    ln: Some(DEFAULT_EXPORT.to_string()),
+                    s: start,
+                    e: end,
+                    ls: *NOT,
+                    le: *NOT,
+                    t: import_index.map(|_| ExportType::ReexportNamed),
+                    is_type: None,
+                    import_index,
+                    kind: None,
+                    mutable: None,
+                });
+                return true;
             }
             // export * as a from 'b'
             ast::ExportSpecifier::Namespace(namespace) => {
@@ -248,6 +566,11 @@ impl ImportExportVisitor {
                         e: ident_span.1,
                         ls: *NOT,
                         le: *NOT,
+                        t: import_index.map(|_| ExportType::ReexportNamespace),
+                        is_type: type_only.then_some(true),
+                        import_index,
+                        kind: None,
+                        mutable: None,
                     });
                     return true;
                 }
@@ -259,30 +582,101 @@ impl ImportExportVisitor {
     fn parse_named_export(&mut self, export: &ast::NamedExport) -> bool {
         // export type { a } from 'b'
         // export type * as a from 'b'
-        if export.type_only {
-            return false;
-        }
-
         // export { type c } from 'b'
-        let is_all_type_export = export.specifiers.iter().all(|specifier| match specifier {
-            ast::ExportSpecifier::Named(named) => named.is_type_only,
-            _ => false,
-        });
-        if is_all_type_export {
+        if is_type_export(export) && !self.include_types {
             return false;
         }
 
+        // the import is added right after the exports
+        let import_index = match &export.src {
+            Some(_) if self.link_reexports => Some(self.imports.len() as i32),
+            _ => None,
+        };
+        let start_index = self.exports.len();
         let mut is_need_add_import = false;
         for specifier in &export.specifiers {
-            let need_add_import = self.parse_export_spec(specifier);
+            let need_add_import = self.parse_export_spec(specifier, export.type_only, import_index);
             if need_add_import && !is_need_add_import {
                 is_need_add_import = true;
             }
         }
+        // export { a as b }
+        self.set_export_kind(start_index, || ExportKind::Binding);
+        // live binding of a local `let` / `var`, unknown of re-exports
+        if self.export_kinds && export.src.is_none() {
+            for local_export in self.exports[start_index..].iter_mut() {
+                local_export.mutable = local_export
+                    .ln
+                    .as_ref()
+                    .and_then(|ln| self.local_bindings.get(ln).copied());
+            }
+        }
         return is_need_add_import;
     }
 
+    fn add_star_export(
+        &mut self,
+        src: &ast::Str,
+        span: Span,
+        namespace: Option<String>,
+        type_only: bool,
+    ) {
+        let (s, e) = self.get_real_span_without_quotes(src.span);
+        let (ss, se) = self.get_real_span(span);
+        let se = self.forward_until_first_not_semi_idx(se);
+        self.star_exports.push(StarExport {
+            n: src.value.to_string(),
+            s,
+            e,
+            ss,
+            se,
+            namespace,
+            is_type: self.include_types.then_some(type_only),
+        });
+    }
+
+    fn parse_star_export(&mut self, export: &ast::ExportAll) {
+        // find the last '*' before the source, skip comments
+        // e.g. export /* * */ * from 'a'
+        let (export_start, _) = self.get_real_span(export.span);
+        let (src_start, _) = self.get_real_span(export.src.span);
+        let comments = self.comments.clone();
+        let (leading, trailing) = comments.borrow_all();
+        let comment_spans = leading
+            .values()
+            .chain(trailing.values())
+            .flatten()
+            .filter(|comment| {
+                comment.span.lo >= export.span.lo && comment.span.hi <= export.src.span.lo
+            })
+            .map(|comment| self.get_real_span(comment.span))
+            .collect::<Vec<_>>();
+        let star = NAMESPACE.encode_utf16().next().unwrap();
+        let start = (export_start..src_start)
+            .rev()
+            .find(|idx| {
+                self.code_utf16[*idx as usize] == star
+                    && !comment_spans.iter().any(|(s, e)| s <= idx && idx < e)
+            })
+            .unwrap_or(*NOT);
+        self.add_export(ExportSpecifier {
+            n: NAMESPACE.to_string(),
+            ln: None,
+            s: start,
+            e: start + 1,
+            ls: *NOT,
+            le: *NOT,
+            t: Some(ExportType::Star),
+            is_type: (self.include_types && export.type_only).then_some(true),
+            // the import is added right after the export
+            import_index: Some(self.imports.len() as i32),
+            kind: None,
+            mutable: None,
+        })
+    }
+
     fn parse_default_export_expr(&mut self, export: &ast::ExportDefaultExpr) {
+        let start_index = self.exports.len();
         let name = DEFAULT_EXPORT.to_string();
         let export_span = self.get_real_span(export.span);
         let find_start = export_span.0 + *EXPORT_LEN;
@@ -296,14 +690,81 @@ impl ImportExportVisitor {
             e: end,
             ls: *NOT,
             le: *NOT,
+            t: None,
+            is_type: None,
+            import_index: None,
+            kind: None,
+            mutable: None,
+        });
+        // export default a
+        // export default 1 + 1
+        self.set_export_kind(start_index, || match &*export.expr {
+            ast::Expr::Ident(_) => ExportKind::Binding,
+            _ => ExportKind::Expression,
+        });
+        // the value is exported, not the binding
+        if self.export_kinds {
+            self.exports[start_index].mutable = Some(false);
+        }
+    }
+
+    fn parse_export_assignment(&mut self, export: &ast::TsExportAssignment) {
+        let export_span = self.get_real_span(export.span);
+        let find_start = export_span.0 + *EXPORT_LEN;
+        // find '=' index start
+        let start = self.find_code_idx_by_string(find_start, *EQUAL);
+        let (ln, ls, le) = match export.expr.as_ref() {
+            // export = a
+            ast::Expr::Ident(ident) => {
+                let (ls, le) = self.get_real_span(ident.span);
+                (Some(ident.sym.to_string()), ls, le)
+            }
+            _ => (None, *NOT, *NOT),
+        };
+        self.add_export(ExportSpecifier {
+            n: DEFAULT_EXPORT.to_string(),
+            ln,
+            s: start,
+            e: start + 1,
+            ls,
+            le,
+            t: Some(ExportType::ExportAssignment),
+            is_type: None,
+            import_index: None,
+            kind: None,
+            mutable: None,
+        })
+    }
+
+    fn parse_namespace_export(&mut self, export: &ast::TsNamespaceExportDecl) {
+        let name = export.id.sym.to_string();
+        let (start, end) = self.get_real_span(export.id.span);
+        self.add_export(ExportSpecifier {
+            n: name,
+            ln: None,
+            s: start,
+            e: end,
+            ls: *NOT,
+            le: *NOT,
+            t: Some(ExportType::NamespaceExport),
+            is_type: None,
+            import_index: None,
+            kind: None,
+            mutable: None,
         })
     }
 
     fn parse_export_decl(&mut self, export: &ast::ExportDecl) -> bool {
         let mut need_eager_return = false;
+        let start_index = self.exports.len();
         match &export.decl {
             ast::Decl::Class(decl) => self.add_export_from_ident(&decl.ident),
             ast::Decl::Fn(decl) => self.add_export_from_ident(&decl.ident),
+            // export const { a: { b }, ...c } = {}
+            ast::Decl::Var(decl) if self.full_destructuring => decl
+                .decls
+                .iter()
+                .for_each(|decl| self.add_export_from_pat(&decl.name)),
             ast::Decl::Var(decl) => {
                 decl.decls.iter().for_each(|decl| {
                     // support export const a = 1, b = 2
@@ -318,6 +779,11 @@ impl ImportExportVisitor {
                                 e: end,
                                 ls: start,
                                 le: end,
+                                t: None,
+                                is_type: None,
+                                import_index: None,
+                                kind: None,
+                                mutable: None,
                             })
                         }
                         ast::Pat::Object(pat) => {
@@ -335,6 +801,11 @@ impl ImportExportVisitor {
                                             e: end,
                                             ls: start,
                                             le: end,
+                                            t: None,
+                                            is_type: None,
+                                            import_index: None,
+                                            kind: None,
+                                            mutable: None,
                                         })
                                     }
                                     ast::ObjectPatProp::KeyValue(kv) => {
@@ -351,6 +822,11 @@ impl ImportExportVisitor {
                                                     e: end,
                                                     ls: start,
                                                     le: end,
+                                                    t: None,
+                                                    is_type: None,
+                                                    import_index: None,
+                                                    kind: None,
+                                                    mutable: None,
                                                 })
                                             }
                                             _ => {
@@ -378,6 +854,11 @@ impl ImportExportVisitor {
                                             e: end,
                                             ls: start,
                                             le: end,
+                                            t: None,
+                                            is_type: None,
+                                            import_index: None,
+                                            kind: None,
+                                            mutable: None,
                                         })
                                     }
                                 }
@@ -398,6 +879,11 @@ impl ImportExportVisitor {
                     e: end,
                     ls: start,
                     le: end,
+                    t: None,
+                    is_type: None,
+                    import_index: None,
+                    kind: None,
+                    mutable: None,
                 })
             }
             ast::Decl::TsModule(decl) => {
@@ -411,6 +897,11 @@ impl ImportExportVisitor {
                         e: end,
                         ls: start,
                         le: end,
+                        t: None,
+                        is_type: None,
+                        import_index: None,
+                        kind: None,
+                        mutable: None,
                     })
                 }
                 // do not visit import / export within namespace
@@ -419,10 +910,25 @@ impl ImportExportVisitor {
             ast::Decl::TsInterface(_) => {}
             ast::Decl::TsTypeAlias(_) => {}
         }
+        match &export.decl {
+            ast::Decl::Class(_) => self.set_export_kind(start_index, || ExportKind::Class),
+            ast::Decl::Fn(decl) => {
+                self.set_export_kind(start_index, || get_fn_kind(&decl.function))
+            }
+            ast::Decl::Var(decl) => self.set_export_kind(start_index, || match decl.kind {
+                ast::VarDeclKind::Const => ExportKind::Const,
+                ast::VarDeclKind::Let => ExportKind::Let,
+                ast::VarDeclKind::Var => ExportKind::Var,
+            }),
+            ast::Decl::TsEnum(_) => self.set_export_kind(start_index, || ExportKind::Enum),
+            ast::Decl::TsModule(_) => self.set_export_kind(start_index, || ExportKind::Namespace),
+            _ => {}
+        }
         need_eager_return
     }
 
     fn parse_export_default_decl(&mut self, export: &ast::ExportDefaultDecl) {
+        let start_index = self.exports.len();
         let export_span = self.get_real_span(export.span);
         let find_start = export_span.0 + *EXPORT_LEN;
         let start = self.find_code_idx_by_string(find_start, *DEFAULT_EXPORT);
@@ -441,6 +947,11 @@ impl ImportExportVisitor {
                         e: end,
                         ls: origin_start,
                         le: origin_end,
+                        t: None,
+                        is_type: None,
+                        import_index: None,
+                        kind: None,
+                        mutable: None,
                     })
                 } else {
                     let name = DEFAULT_EXPORT.to_string();
@@ -451,6 +962,11 @@ impl ImportExportVisitor {
                         e: end,
                         ls: *NOT,
                         le: *NOT,
+                        t: None,
+                        is_type: None,
+                        import_index: None,
+                        kind: None,
+                        mutable: None,
                     })
                 }
             }
@@ -467,6 +983,11 @@ impl ImportExportVisitor {
                         e: end,
                         ls: origin_start,
                         le: origin_end,
+                        t: None,
+                        is_type: None,
+                        import_index: None,
+                        kind: None,
+                        mutable: None,
                     })
                 } else {
                     let name = DEFAULT_EXPORT.to_string();
@@ -477,14 +998,1018 @@ impl ImportExportVisitor {
                         e: end,
                         ls: *NOT,
                         le: *NOT,
+                        t: None,
+                        is_type: None,
+                        import_index: None,
+                        kind: None,
+                        mutable: None,
                     })
                 }
             }
             ast::DefaultDecl::TsInterfaceDecl(_) => {}
         }
+        match &export.decl {
+            ast::DefaultDecl::Class(_) => self.set_export_kind(start_index, || ExportKind::Class),
+            ast::DefaultDecl::Fn(decl) => {
+                self.set_export_kind(start_index, || get_fn_kind(&decl.function))
+            }
+            ast::DefaultDecl::TsInterfaceDecl(_) => {}
+        }
+    }
+}
+
+// type references
+impl ImportExportVisitor {
+    // /// <reference path="./a.d.ts" />
+    // /// <reference types="node" />
+    pub fn parse_references(&mut self, program: &ast::Program) {
+        if !self.type_references {
+            return;
+        }
+
+        // directives are only valid before the first statement
+        let body_start = match program {
+            ast::Program::Module(module) => module.body.first().map(|item| item.span_lo()),
+            ast::Program::Script(script) => script.body.first().map(|stmt| stmt.span_lo()),
+        };
+        let comments = self.comments.clone();
+        let (leading, trailing) = comments.borrow_all();
+        let mut directives = leading
+            .values()
+            .chain(trailing.values())
+            .flatten()
+            .filter(|comment| comment.kind == CommentKind::Line)
+            .filter(|comment| body_start.is_none_or(|start| comment.span.hi <= start))
+            .collect::<Vec<_>>();
+        directives.sort_by_key(|comment| comment.span.lo);
+
+        for comment in directives {
+            let text = comment.text.as_str();
+            let Some(directive) = text.strip_prefix('/') else {
+                continue;
+            };
+            let directive = directive.trim_start();
+            if !directive.starts_with(*REFERENCE) {
+                continue;
+            }
+            let (t, attr) = if directive.contains(*REFERENCE_PATH) {
+                (ImportType::ReferencePath, *REFERENCE_PATH)
+            } else if directive.contains(*REFERENCE_TYPES) {
+                (ImportType::ReferenceTypes, *REFERENCE_TYPES)
+            } else {
+                continue;
+            };
+            // value of `path="…"` / `types='…'`
+            let value_start = text.find(attr).unwrap() + attr.len() + 1;
+            let Some(quote) = text[value_start - 1..].chars().next() else {
+                continue;
+            };
+            if quote != '"' && quote != '\'' {
+                continue;
+            }
+            let Some(value_len) = text[value_start..].find(quote) else {
+                continue;
+            };
+            let name = text[value_start..value_start + value_len].to_string();
+            // skip leading `//`
+            let lo = comment.span.lo + BytePos(2 + value_start as u32);
+            let hi = lo + BytePos(value_len as u32);
+            let (start, end) = self.get_real_span(Span::new(lo, hi));
+            let (ss, se) = self.get_real_span(comment.span);
+            self.add_import(ImportSpecifier {
+                n: Some(name),
+                s: start,
+                e: end,
+                ss,
+                se,
+                d: *NOT,
+                a: *NOT,
+                t,
+                at: None,
+                pattern: None,
+                bindings: None,
+                is_type: Some(true),
+                magic_comments: None,
+                glob: None,
+                meta: None,
+            });
+        }
+    }
+
+    // typeof import('./a')
+    // import('./a').A
+    fn parse_type_import(&mut self, import: &ast::TsImportType) {
+        let name = import.arg.value.to_string();
+        let (start, end) = self.get_real_span_without_quotes(import.arg.span);
+        let (ss, se) = self.get_real_span(import.span);
+        self.add_import(ImportSpecifier {
+            n: Some(name),
+            s: start,
+            e: end,
+            ss,
+            se,
+            d: *NOT,
+            a: *NOT,
+            t: ImportType::TypeImport,
+            at: None,
+            pattern: None,
+            bindings: None,
+            is_type: Some(true),
+            magic_comments: None,
+            glob: None,
+            meta: None,
+        });
+    }
+}
+
+// jsdoc
+impl ImportExportVisitor {
+    // /** @import { A } from './a' */
+    // /** @type {import('./a').A} */
+    pub fn parse_jsdoc(&mut self) {
+        if !self.jsdoc {
+            return;
+        }
+
+        let comments = self.comments.clone();
+        let (leading, trailing) = comments.borrow_all();
+        let mut docs = leading
+            .values()
+            .chain(trailing.values())
+            .flatten()
+            .filter(|comment| comment.kind == CommentKind::Block && comment.text.starts_with('*'))
+            .collect::<Vec<_>>();
+        docs.sort_by_key(|comment| comment.span.lo);
+
+        let mut jsdoc_imports = vec![];
+        for comment in docs {
+            let (ss, se) = self.get_real_span(comment.span);
+            for (t, value_start, value_len) in find_jsdoc_imports(&comment.text) {
+                // skip leading `/*`
+                let lo = comment.span.lo + BytePos(2 + value_start as u32);
+                let hi = lo + BytePos(value_len as u32);
+                let (s, e) = self.get_real_span(Span::new(lo, hi));
+                jsdoc_imports.push(JsDocImport {
+                    n: comment.text[value_start..value_start + value_len].to_string(),
+                    // relative to the comment
+                    s: s - ss,
+                    e: e - ss,
+                    ss,
+                    se,
+                    t,
+                });
+            }
+        }
+        self.jsdoc_imports = Some(jsdoc_imports);
+    }
+}
+
+// magic comments
+impl ImportExportVisitor {
+    fn get_magic_comments(&self, args: &[ast::ExprOrSpread]) -> Option<Vec<MagicComment>> {
+        if !self.magic_comments {
+            return None;
+        }
+
+        let mut magic_comments = vec![];
+        for arg in args {
+            let Some(comments) = self.comments.get_leading(arg.span_lo()) else {
+                continue;
+            };
+            for comment in comments {
+                let (s, e) = self.get_real_span(comment.span);
+                for (key, value) in parse_magic_comment(&comment.text) {
+                    magic_comments.push(MagicComment { key, value, s, e });
+                }
+            }
+        }
+        Some(magic_comments)
+    }
+}
+
+// assets
+impl ImportExportVisitor {
+    // new URL('./a', import.meta.url)
+    // new Worker(new URL('./a', import.meta.url))
+    fn parse_new_expr(&mut self, new: &ast::NewExpr) {
+        let is_worker =
+            is_ident_expr(&new.callee, *WORKER) || is_ident_expr(&new.callee, *SHARED_WORKER);
+        if is_worker {
+            let url = new.args.as_ref().and_then(|args| args.first());
+            if let Some(ast::Expr::New(url)) = url.map(|arg| arg.expr.as_ref()) {
+                if self.parse_asset_url(url, new.span, ImportType::Worker) {
+                    self.worker_url_span = Some(url.span);
+                }
+            }
+            return;
+        }
+        if self.worker_url_span == Some(new.span) {
+            return;
+        }
+        self.parse_asset_url(new, new.span, ImportType::UrlAsset);
+    }
+
+    fn parse_asset_url(&mut self, url: &ast::NewExpr, span: Span, t: ImportType) -> bool {
+        if !is_ident_expr(&url.callee, *URL) {
+            return false;
+        }
+        let args = match &url.args {
+            Some(args) if args.len() == 2 => args,
+            _ => return false,
+        };
+        if !is_import_meta_url(&args[1].expr) {
+            return false;
+        }
+
+        let arg = &args[0];
+        let (name, (start, end)) = match arg.expr.as_ref() {
+            // new URL('./a', import.meta.url)
+            ast::Expr::Lit(ast::Lit::Str(src)) => (
+                Some(src.value.to_string()),
+                self.get_real_span_without_quotes(src.span),
+            ),
+            expr if self.resolve_static_specifier => {
+                (get_static_string(expr), self.get_real_span(arg.span()))
+            }
+            _ => (None, self.get_real_span(arg.span())),
+        };
+        // new URL(`./img/${name}.png`, import.meta.url)
+        let mut pattern = None;
+        if self.dynamic_import_pattern && name.is_none() {
+            pattern = self.get_dynamic_import_pattern(&arg.expr);
+        }
+        let (ss, se) = self.get_real_span(span);
+        self.add_import(ImportSpecifier {
+            n: name,
+            s: start,
+            e: end,
+            ss,
+            se,
+            d: *NOT,
+            a: *NOT,
+            t,
+            at: None,
+            pattern,
+            bindings: None,
+            is_type: None,
+            magic_comments: None,
+            glob: None,
+            meta: None,
+        });
+        true
+    }
+}
+
+// glob imports
+impl ImportExportVisitor {
+    // import.meta.glob('./dir/*.js', { eager: true })
+    // require.context('./dir', true, /\.js$/)
+    fn parse_glob_import(&mut self, call: &ast::CallExpr) {
+        let ast::Callee::Expr(callee) = &call.callee else {
+            return;
+        };
+        let ast::Expr::Member(member) = callee.as_ref() else {
+            return;
+        };
+        let prop = get_member_prop_name(&member.prop);
+        let t = match member.obj.as_ref() {
+            ast::Expr::MetaProp(meta)
+                if meta.kind == ast::MetaPropKind::ImportMeta && prop.as_deref() == Some(*GLOB) =>
+            {
+                ImportType::ImportMetaGlob
+            }
+            obj if is_ident_expr(obj, *REQUIRE) && prop.as_deref() == Some(*CONTEXT) => {
+                ImportType::RequireContext
+            }
+            _ => return,
+        };
+        let arg = match call.args.first() {
+            Some(arg) if arg.spread.is_none() => arg,
+            _ => return,
+        };
+
+        // patterns must be static
+        let patterns = match (&t, arg.expr.as_ref()) {
+            // import.meta.glob(['./a/*.js', '!./a/b.js'])
+            (ImportType::ImportMetaGlob, ast::Expr::Array(array)) => array
+                .elems
+                .iter()
+                .map(|elem| match elem {
+                    Some(elem) if elem.spread.is_none() => get_static_string(&elem.expr),
+                    _ => None,
+                })
+                .collect::<Option<Vec<String>>>(),
+            (_, expr) => get_static_string(expr).map(|pattern| vec![pattern]),
+        };
+        let Some(patterns) = patterns else {
+            return;
+        };
+
+        let options_args = &call.args[1..];
+        let (os, oe, options) = match (options_args.first(), options_args.last()) {
+            (Some(first), Some(last)) => {
+                let (os, _) = self.get_real_span(first.span());
+                let (_, oe) = self.get_real_span(last.span());
+                let options = match t {
+                    // { eager: true, import: 'default' }
+                    ImportType::ImportMetaGlob => match first.expr.as_ref() {
+                        ast::Expr::Object(obj) => self.get_literal_options(obj),
+                        _ => vec![],
+                    },
+                    // true, /\.js$/, 'lazy'
+                    _ => REQUIRE_CONTEXT_OPTIONS
+                        .iter()
+                        .zip(options_args)
+                        .map(|(key, arg)| vec![key.to_string(), self.get_literal_value(&arg.expr)])
+                        .collect(),
+                };
+                (os, oe, options)
+            }
+            _ => (*NOT, *NOT, vec![]),
+        };
+
+        let (ss, se) = self.get_real_span(call.span);
+        let mut name = None;
+        let (mut start, mut end) = self.get_real_span(arg.span());
+        if let ast::Expr::Lit(ast::Lit::Str(src)) = arg.expr.as_ref() {
+            name = Some(src.value.to_string());
+            (start, end) = self.get_real_span_without_quotes(src.span);
+        }
+        self.add_import(ImportSpecifier {
+            n: name,
+            s: start,
+            e: end,
+            ss,
+            se,
+            d: *NOT,
+            a: *NOT,
+            t,
+            at: None,
+            pattern: None,
+            bindings: None,
+            is_type: None,
+            magic_comments: None,
+            glob: Some(GlobImport {
+                patterns,
+                options,
+                os,
+                oe,
+            }),
+            meta: None,
+        });
+    }
+
+    fn get_literal_options(&self, obj: &ast::ObjectLit) -> Vec<Vec<String>> {
+        obj.props
+            .iter()
+            .filter_map(|prop| match prop {
+                ast::PropOrSpread::Prop(prop) => match prop.as_ref() {
+                    ast::Prop::KeyValue(kv) => {
+                        let (key, _) = self.get_prop_name(&kv.key)?;
+                        Some(vec![key, self.get_literal_value(&kv.value)])
+                    }
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    // strings are unquoted, others are kept as written
+    fn get_literal_value(&self, expr: &ast::Expr) -> String {
+        match expr {
+            ast::Expr::Lit(ast::Lit::Str(str)) => str.value.to_string(),
+            expr => {
+                let (start, end) = self.get_real_span(expr.span());
+                String::from_utf16_lossy(&self.code_utf16[start as usize..end as usize])
+            }
+        }
     }
 }
 
+// import.meta members
+impl ImportExportVisitor {
+    // import.meta.env.MODE
+    // import.meta.hot?.accept()
+    fn parse_import_meta_member(&mut self, expr: &ast::Expr) {
+        // the outermost chain is visited first
+        if self.import_meta_member.is_some() {
+            return;
+        }
+        let (callee, call) = match get_call(expr) {
+            Some((callee, _, _)) => (callee, true),
+            None => (expr, false),
+        };
+        let Some((meta_span, props, hi)) = get_import_meta_chain(callee) else {
+            return;
+        };
+        // import.meta[key]
+        let call = call && hi == callee.span_hi();
+        let (s, e) = self.get_real_span(Span::new(meta_span.lo, hi));
+        self.import_meta_member = Some((meta_span, ImportMetaMember { props, s, e, call }));
+    }
+
+    fn get_import_meta_member(&mut self, meta: &ast::MetaPropExpr) -> Option<ImportMetaMember> {
+        if !self.import_meta_members {
+            return None;
+        }
+        match self.import_meta_member.take() {
+            Some((span, member)) if span == meta.span => Some(member),
+            other => {
+                self.import_meta_member = other;
+                let (s, e) = self.get_real_span(meta.span);
+                Some(ImportMetaMember {
+                    props: vec![],
+                    s,
+                    e,
+                    call: false,
+                })
+            }
+        }
+    }
+}
+
+// hmr
+impl ImportExportVisitor {
+    // import.meta.hot.accept('./a', cb)
+    // module.hot.accept(['./a', './b'], cb)
+    fn parse_hmr_call(&mut self, expr: &ast::Expr) {
+        let Some((callee, args, span)) = get_call(expr) else {
+            return;
+        };
+        let Some(method) = get_hot_method(callee) else {
+            return;
+        };
+        let t = match method.as_str() {
+            "accept" => HmrType::Accept,
+            "dispose" => HmrType::Dispose,
+            "decline" => HmrType::Decline,
+            "invalidate" => HmrType::Invalidate,
+            _ => return,
+        };
+
+        let mut deps = vec![];
+        if matches!(t, HmrType::Accept | HmrType::Decline) {
+            let srcs = match args.first().map(|arg| arg.expr.as_ref()) {
+                // accept('./a')
+                Some(ast::Expr::Lit(ast::Lit::Str(src))) => vec![src],
+                // accept(['./a', './b'])
+                Some(ast::Expr::Array(array)) => array
+                    .elems
+                    .iter()
+                    .filter_map(|elem| match elem.as_ref()?.expr.as_ref() {
+                        ast::Expr::Lit(ast::Lit::Str(src)) => Some(src),
+                        _ => None,
+                    })
+                    .collect(),
+                // accept(cb)
+                _ => vec![],
+            };
+            for src in srcs {
+                let (s, e) = self.get_real_span_without_quotes(src.span);
+                deps.push(HmrDependency {
+                    n: src.value.to_string(),
+                    s,
+                    e,
+                });
+            }
+        }
+
+        let (ss, se) = self.get_real_span(span);
+        if let Some(hmr) = self.hmr.as_mut() {
+            hmr.push(HmrCall { t, deps, ss, se });
+        }
+    }
+}
+
+// dynamic import pattern
+impl ImportExportVisitor {
+    fn get_dynamic_import_pattern(&self, expr: &ast::Expr) -> Option<DynamicImportPattern> {
+        let mut parts = vec![String::new()];
+        let mut placeholders = vec![];
+        self.collect_pattern_parts(expr, &mut parts, &mut placeholders);
+        // fully static or fully dynamic
+        if placeholders.is_empty() || parts.iter().all(|part| part.is_empty()) {
+            return None;
+        }
+
+        let mut glob = String::new();
+        for (idx, part) in parts.iter().enumerate() {
+            glob.push_str(part);
+            // `${a}${b}` will be one `*`
+            if idx < placeholders.len() && !glob.ends_with('*') {
+                glob.push('*');
+            }
+        }
+        Some(DynamicImportPattern {
+            glob,
+            parts,
+            placeholders,
+        })
+    }
+
+    fn collect_pattern_parts(
+        &self,
+        expr: &ast::Expr,
+        parts: &mut Vec<String>,
+        placeholders: &mut Vec<Range>,
+    ) {
+        match expr {
+            // './a'
+            ast::Expr::Lit(ast::Lit::Str(str)) => {
+                parts.last_mut().unwrap().push_str(&str.value.to_string());
+            }
+            // `./${a}.js`
+            ast::Expr::Tpl(tpl) => {
+                for (idx, quasi) in tpl.quasis.iter().enumerate() {
+                    let value = match &quasi.cooked {
+                        Some(cooked) => cooked.to_string(),
+                        None => quasi.raw.to_string(),
+                    };
+                    parts.last_mut().unwrap().push_str(&value);
+                    if let Some(expr) = tpl.exprs.get(idx) {
+                        self.collect_pattern_parts(expr, parts, placeholders);
+                    }
+                }
+            }
+            // './' + a + '.js'
+            ast::Expr::Bin(bin) if bin.op == ast::BinaryOp::Add => {
+                self.collect_pattern_parts(&bin.left, parts, placeholders);
+                self.collect_pattern_parts(&bin.right, parts, placeholders);
+            }
+            ast::Expr::Paren(paren) => {
+                self.collect_pattern_parts(&paren.expr, parts, placeholders);
+            }
+            // variable
+            _ => {
+                let (s, e) = self.get_real_span(expr.span());
+                placeholders.push(Range { s, e });
+                parts.push(String::new());
+            }
+        }
+    }
+}
+
+// commonjs
+impl ImportExportVisitor {
+    fn parse_require(&mut self, call: &ast::CallExpr) {
+        let t = match &call.callee {
+            ast::Callee::Expr(callee) => match callee.as_ref() {
+                // require('a')
+                ast::Expr::Ident(ident) if ident.sym == *REQUIRE => ImportType::Require,
+                // require.resolve('a')
+                ast::Expr::Member(member)
+                    if is_ident_expr(&member.obj, *REQUIRE)
+                        && get_member_prop_name(&member.prop).as_deref() == Some(*RESOLVE) =>
+                {
+                    ImportType::RequireResolve
+                }
+                _ => return,
+            },
+            _ => return,
+        };
+        let arg = match call.args.first() {
+            Some(arg) if arg.spread.is_none() => arg,
+            _ => return,
+        };
+
+        let (ss, se) = self.get_real_span(call.span);
+        let mut name = None;
+        let (mut start, mut end) = self.get_real_span(arg.span());
+        if let ast::Expr::Lit(ast::Lit::Str(src)) = arg.expr.as_ref() {
+            name = Some(src.value.to_string());
+            (start, end) = self.get_real_span_without_quotes(src.span);
+        }
+        self.add_import(ImportSpecifier {
+            n: name,
+            s: start,
+            e: end,
+            ss,
+            se,
+            d: *NOT,
+            a: *NOT,
+            t,
+            at: None,
+            pattern: None,
+            bindings: None,
+            is_type: None,
+            magic_comments: None,
+            glob: None,
+            meta: None,
+        });
+    }
+
+    fn add_cjs_export(&mut self, name: String, span: (i32, i32), t: ExportType) {
+        self.add_export(ExportSpecifier {
+            n: name,
+            ln: None,
+            s: span.0,
+            e: span.1,
+            ls: *NOT,
+            le: *NOT,
+            t: Some(t),
+            is_type: None,
+            import_index: None,
+            kind: None,
+            mutable: None,
+        })
+    }
+
+    fn parse_cjs_assign(&mut self, assign: &ast::AssignExpr) {
+        if assign.op != ast::AssignOp::Assign {
+            return;
+        }
+        let member = match &assign.left {
+            ast::AssignTarget::Simple(ast::SimpleAssignTarget::Member(member)) => member,
+            _ => return,
+        };
+
+        // module.exports = a
+        if is_module_exports(member) {
+            let span = self.get_real_span(member.span);
+            self.add_cjs_export(DEFAULT_EXPORT.to_string(), span, ExportType::ModuleExports);
+            // module.exports = { a, b: c }
+            if let ast::Expr::Object(obj) = assign.right.as_ref() {
+                obj.props.iter().for_each(|prop| {
+                    if let ast::PropOrSpread::Prop(prop) = prop {
+                        let key = match prop.as_ref() {
+                            ast::Prop::Shorthand(ident) => {
+                                Some((ident.sym.to_string(), self.get_real_span(ident.span)))
+                            }
+                            ast::Prop::KeyValue(kv) => self.get_prop_name(&kv.key),
+                            ast::Prop::Method(method) => self.get_prop_name(&method.key),
+                            _ => None,
+                        };
+                        if let Some((name, span)) = key {
+                            self.add_cjs_export(name, span, ExportType::ModuleExports);
+                        }
+                    }
+                })
+            }
+            return;
+        }
+
+        // exports.a = 1
+        // module.exports.a = 1
+        let is_exports = match member.obj.as_ref() {
+            ast::Expr::Member(obj) => is_module_exports(obj),
+            obj => is_ident_expr(obj, *EXPORTS),
+        };
+        if is_exports {
+            if let Some((name, span)) = self.get_member_prop(&member.prop) {
+                self.add_cjs_export(name, span, ExportType::Exports);
+            }
+        }
+    }
+
+    fn parse_define_property(&mut self, call: &ast::CallExpr) {
+        // Object.defineProperty(exports, 'a', { value: 1 })
+        let is_define_property = match &call.callee {
+            ast::Callee::Expr(callee) => match callee.as_ref() {
+                ast::Expr::Member(member) => {
+                    is_ident_expr(&member.obj, *OBJECT)
+                        && get_member_prop_name(&member.prop).as_deref() == Some(*DEFINE_PROPERTY)
+                }
+                _ => false,
+            },
+            _ => false,
+        };
+        if !is_define_property || call.args.len() < 2 {
+            return;
+        }
+        let is_exports = match call.args[0].expr.as_ref() {
+            ast::Expr::Member(obj) => is_module_exports(obj),
+            obj => is_ident_expr(obj, *EXPORTS),
+        };
+        if !is_exports {
+            return;
+        }
+        if let ast::Expr::Lit(ast::Lit::Str(str)) = call.args[1].expr.as_ref() {
+            let span = self.get_real_span_without_quotes(str.span);
+            self.add_cjs_export(str.value.to_string(), span, ExportType::DefineProperty);
+        }
+    }
+
+    fn get_member_prop(&self, prop: &ast::MemberProp) -> Option<(String, (i32, i32))> {
+        match prop {
+            // a.b
+            ast::MemberProp::Ident(ident) => {
+                Some((ident.sym.to_string(), self.get_real_span(ident.span)))
+            }
+            // a['b']
+            ast::MemberProp::Computed(computed) => match computed.expr.as_ref() {
+                ast::Expr::Lit(ast::Lit::Str(str)) => Some((
+                    str.value.to_string(),
+                    self.get_real_span_without_quotes(str.span),
+                )),
+                _ => None,
+            },
+            ast::MemberProp::PrivateName(_) => None,
+        }
+    }
+
+    fn get_prop_name(&self, key: &ast::PropName) -> Option<(String, (i32, i32))> {
+        match key {
+            // { a: 1 }
+            ast::PropName::Ident(ident) => {
+                Some((ident.sym.to_string(), self.get_real_span(ident.span)))
+            }
+            // { 'a': 1 }
+            ast::PropName::Str(str) => Some((
+                str.value.to_string(),
+                self.get_real_span_without_quotes(str.span),
+            )),
+            _ => None,
+        }
+    }
+}
+
+fn is_ident_expr(expr: &ast::Expr, name: &str) -> bool {
+    matches!(expr, ast::Expr::Ident(ident) if ident.sym == *name)
+}
+
+// `abc`
+// 'a' + `b`
+fn get_static_string(expr: &ast::Expr) -> Option<String> {
+    match expr {
+        ast::Expr::Lit(ast::Lit::Str(str)) => Some(str.value.to_string()),
+        ast::Expr::Tpl(tpl) if tpl.exprs.is_empty() => tpl
+            .quasis
+            .first()
+            .and_then(|quasi| quasi.cooked.as_ref())
+            .map(|cooked| cooked.to_string()),
+        ast::Expr::Bin(bin) if bin.op == ast::BinaryOp::Add => {
+            let left = get_static_string(&bin.left)?;
+            let right = get_static_string(&bin.right)?;
+            Some(format!("{left}{right}"))
+        }
+        ast::Expr::Paren(paren) => get_static_string(&paren.expr),
+        _ => None,
+    }
+}
+
+fn get_member_prop_name(prop: &ast::MemberProp) -> Option<String> {
+    match prop {
+        ast::MemberProp::Ident(ident) => Some(ident.sym.to_string()),
+        ast::MemberProp::Computed(computed) => match computed.expr.as_ref() {
+            ast::Expr::Lit(ast::Lit::Str(str)) => Some(str.value.to_string()),
+            _ => None,
+        },
+        ast::MemberProp::PrivateName(_) => None,
+    }
+}
+
+// webpackChunkName: "a", webpackPrefetch: true
+// @vite-ignore
+fn parse_magic_comment(text: &str) -> Vec<(String, Option<String>)> {
+    let text = text.trim().trim_start_matches('*').trim();
+    // vite style flag
+    if text.starts_with('@') {
+        return vec![(text.to_string(), None)];
+    }
+
+    // split by top level `,`, e.g. webpackExports: ["a", "b"]
+    let mut pairs = vec![];
+    let mut depth = 0;
+    let mut quote = None;
+    let mut start = 0;
+    for (idx, char) in text.char_indices() {
+        match (quote, char) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'' | '`') => quote = Some(char),
+            (None, '[' | '{' | '(') => depth += 1,
+            (None, ']' | '}' | ')') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                pairs.push(&text[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    pairs.push(&text[start..]);
+
+    pairs
+        .into_iter()
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once(':')?;
+            let key = key.trim();
+            if key.is_empty() || !key.chars().all(is_ident_char) {
+                return None;
+            }
+            let value = value.trim();
+            let value = match value.chars().next() {
+                Some(q @ ('"' | '\'' | '`')) if value.len() > 1 && value.ends_with(q) => {
+                    &value[1..value.len() - 1]
+                }
+                _ => value,
+            };
+            Some((key.to_string(), Some(value.to_string())))
+        })
+        .collect()
+}
+
+// @import { a } from 'b'
+// import('b')
+// returns type, byte start and byte length of the sources in text
+fn find_jsdoc_imports(text: &str) -> Vec<(JsDocImportType, usize, usize)> {
+    let mut list = vec![];
+    let mut idx = 0;
+    while let Some(pos) = text[idx..].find(*IMPORT) {
+        let start = idx + pos;
+        idx = start + IMPORT.len();
+        // skip e.g. `reimport(` / `@importer`
+        if text[..start].chars().next_back().is_some_and(is_ident_char)
+            || text[idx..].chars().next().is_some_and(is_ident_char)
+        {
+            continue;
+        }
+        if text[..start].ends_with('@') {
+            // the tag ends at the next tag
+            let end = text[idx..].find('@').map_or(text.len(), |pos| idx + pos);
+            let source = text[idx..end]
+                .match_indices(*FROM)
+                .find_map(|(from, _)| read_quoted(text, idx + from + FROM.len()));
+            if let Some((value_start, value_len)) = source {
+                list.push((JsDocImportType::Import, value_start, value_len));
+            }
+        } else if text[idx..].trim_start().starts_with('(') {
+            let paren = idx + text[idx..].find('(').unwrap();
+            if let Some((value_start, value_len)) = read_quoted(text, paren + 1) {
+                list.push((JsDocImportType::TypeImport, value_start, value_len));
+            }
+        }
+    }
+    list
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+// ` 'a'` -> byte start and byte length of `a`
+fn read_quoted(text: &str, start: usize) -> Option<(usize, usize)> {
+    let rest = &text[start..];
+    let trimmed = rest.trim_start();
+    let quote = trimmed.chars().next()?;
+    if quote != '"' && quote != '\'' {
+        return None;
+    }
+    let value_start = start + (rest.len() - trimmed.len()) + 1;
+    let value_len = text[value_start..].find(quote)?;
+    Some((value_start, value_len))
+}
+
+// import type { a } from 'b'
+// import { type a } from 'b'
+fn is_type_import(import: &ast::ImportDecl) -> bool {
+    import.type_only
+        || (!import.specifiers.is_empty()
+            && import.specifiers.iter().all(|specifier| match specifier {
+                ast::ImportSpecifier::Named(named) => named.is_type_only,
+                _ => false,
+            }))
+}
+
+fn get_fn_kind(function: &ast::Function) -> ExportKind {
+    match (function.is_async, function.is_generator) {
+        (_, true) => ExportKind::Generator,
+        (true, false) => ExportKind::AsyncFunction,
+        (false, false) => ExportKind::Function,
+    }
+}
+
+// { a: [b, ...c] } -> b, c
+fn get_pat_names(pat: &ast::Pat, names: &mut Vec<String>) {
+    match pat {
+        ast::Pat::Ident(ident) => names.push(ident.sym.to_string()),
+        ast::Pat::Array(array) => array
+            .elems
+            .iter()
+            .flatten()
+            .for_each(|elem| get_pat_names(elem, names)),
+        ast::Pat::Object(obj) => obj.props.iter().for_each(|prop| match prop {
+            ast::ObjectPatProp::KeyValue(kv) => get_pat_names(&kv.value, names),
+            ast::ObjectPatProp::Assign(assign) => names.push(assign.key.sym.to_string()),
+            ast::ObjectPatProp::Rest(rest) => get_pat_names(&rest.arg, names),
+        }),
+        ast::Pat::Rest(rest) => get_pat_names(&rest.arg, names),
+        ast::Pat::Assign(assign) => get_pat_names(&assign.left, names),
+        ast::Pat::Expr(_) | ast::Pat::Invalid(_) => {}
+    }
+}
+
+// export type { a } from 'b'
+// export { type a } from 'b'
+fn is_type_export(export: &ast::NamedExport) -> bool {
+    export.type_only
+        || (!export.specifiers.is_empty()
+            && export.specifiers.iter().all(|specifier| match specifier {
+                ast::ExportSpecifier::Named(named) => named.is_type_only,
+                _ => false,
+            }))
+}
+
+// a()
+// a?.()
+// returns callee, arguments and span of the call
+fn get_call(expr: &ast::Expr) -> Option<(&ast::Expr, &[ast::ExprOrSpread], Span)> {
+    match expr {
+        ast::Expr::Call(ast::CallExpr {
+            callee: ast::Callee::Expr(callee),
+            args,
+            span,
+            ..
+        }) => Some((callee.as_ref(), args, *span)),
+        ast::Expr::OptChain(ast::OptChainExpr { base, span, .. }) => match base.as_ref() {
+            ast::OptChainBase::Call(call) => Some((call.callee.as_ref(), &call.args, *span)),
+            ast::OptChainBase::Member(_) => None,
+        },
+        _ => None,
+    }
+}
+
+// import.meta.hot.accept
+// module.hot.accept
+fn get_hot_method(callee: &ast::Expr) -> Option<String> {
+    if let Some((_, props, hi)) = get_import_meta_chain(callee) {
+        return match props.as_slice() {
+            [hot, method] if hot == *HOT && hi == callee.span_hi() => Some(method.to_string()),
+            _ => None,
+        };
+    }
+    let member = match callee {
+        ast::Expr::Member(member) => member,
+        ast::Expr::OptChain(ast::OptChainExpr { base, .. }) => match base.as_ref() {
+            ast::OptChainBase::Member(member) => member,
+            ast::OptChainBase::Call(_) => return None,
+        },
+        _ => return None,
+    };
+    let hot = match member.obj.as_ref() {
+        ast::Expr::Member(hot) => hot,
+        ast::Expr::OptChain(ast::OptChainExpr { base, .. }) => match base.as_ref() {
+            ast::OptChainBase::Member(hot) => hot,
+            ast::OptChainBase::Call(_) => return None,
+        },
+        _ => return None,
+    };
+    if !is_ident_expr(&hot.obj, *MODULE) || get_member_prop_name(&hot.prop).as_deref() != Some(*HOT)
+    {
+        return None;
+    }
+    get_member_prop_name(&member.prop)
+}
+
+// import.meta.a.b
+// returns span of `import.meta`, property names and end of the named chain
+fn get_import_meta_chain(expr: &ast::Expr) -> Option<(Span, Vec<String>, BytePos)> {
+    let member = match expr {
+        ast::Expr::Member(member) => member,
+        ast::Expr::OptChain(ast::OptChainExpr { base, .. }) => match base.as_ref() {
+            ast::OptChainBase::Member(member) => member,
+            ast::OptChainBase::Call(_) => return None,
+        },
+        ast::Expr::Paren(paren) => return get_import_meta_chain(&paren.expr),
+        _ => return None,
+    };
+    let (meta_span, mut props, hi) = match member.obj.as_ref() {
+        ast::Expr::MetaProp(meta) if meta.kind == ast::MetaPropKind::ImportMeta => {
+            (meta.span, vec![], meta.span.hi)
+        }
+        obj => get_import_meta_chain(obj)?,
+    };
+    // stop at the first computed property, e.g. import.meta.env[key]
+    if hi != member.obj.span_hi() {
+        return Some((meta_span, props, hi));
+    }
+    match get_member_prop_name(&member.prop) {
+        Some(name) => {
+            props.push(name);
+            Some((meta_span, props, member.span.hi))
+        }
+        None => Some((meta_span, props, hi)),
+    }
+}
+
+// import.meta.url
+fn is_import_meta_url(expr: &ast::Expr) -> bool {
+    let ast::Expr::Member(member) = expr else {
+        return false;
+    };
+    let is_import_meta = matches!(
+        member.obj.as_ref(),
+        ast::Expr::MetaProp(meta) if meta.kind == ast::MetaPropKind::ImportMeta
+    );
+    is_import_meta && get_member_prop_name(&member.prop).as_deref() == Some(*META_URL)
+}
+
+// module.exports
+fn is_module_exports(member: &ast::MemberExpr) -> bool {
+    is_ident_expr(&member.obj, *MODULE)
+        && get_member_prop_name(&member.prop).as_deref() == Some(*EXPORTS)
+}
+
 // utils
 impl ImportExportVisitor {
     // legacy: imports.asserts
@@ -697,6 +2222,54 @@ impl ImportExportVisitor {
         self.has_module_syntax = value;
     }
 
+    fn detect_cjs_syntax(&mut self, node: &ast::Expr) {
+        if self.function_depth > 0 || self.has_cjs_syntax {
+            return;
+        }
+        let is_cjs = match node {
+            // require('a')
+            ast::Expr::Call(call) => match &call.callee {
+                ast::Callee::Expr(callee) => is_ident_expr(callee, *REQUIRE),
+                _ => false,
+            },
+            // module.exports
+            ast::Expr::Member(member) => is_module_exports(member),
+            // module.exports = a
+            // exports.a = 1
+            ast::Expr::Assign(assign) => match &assign.left {
+                ast::AssignTarget::Simple(ast::SimpleAssignTarget::Member(member)) => {
+                    is_module_exports(member) || is_ident_expr(&member.obj, *EXPORTS)
+                }
+                _ => false,
+            },
+            _ => false,
+        };
+        if is_cjs {
+            self.has_cjs_syntax = true;
+        }
+    }
+
+    // `start` is where to find the `await` keyword
+    fn detect_top_level_await(&mut self, start: i32) {
+        if self.function_depth > 0 || self.top_level_await.is_some() {
+            return;
+        }
+        let s = self.find_code_idx_by_string(start, *AWAIT);
+        self.top_level_await = Some(Range {
+            s,
+            e: s + *AWAIT_LEN,
+        });
+    }
+
+    fn detect_module_kind(&mut self) {
+        self.module_kind = match (self.has_module_syntax, self.has_cjs_syntax) {
+            (true, false) => ModuleKind::Esm,
+            (false, true) => ModuleKind::Cjs,
+            (true, true) => ModuleKind::Mixed,
+            (false, false) => ModuleKind::Script,
+        };
+    }
+
     fn detect_syntax(&mut self, module: &mut ast::Module) {
         let mut has_module_syntax = false;
         for item in module.body.iter() {
@@ -716,6 +2289,7 @@ impl VisitMut for ImportExportVisitor {
         self.detect_facade(module);
         self.detect_syntax(module);
         module.visit_mut_children_with(self);
+        self.detect_module_kind();
     }
 
     // normal
@@ -750,7 +2324,28 @@ impl VisitMut for ImportExportVisitor {
                             a,
                             t: ImportType::Static,
                             at: attrs,
-                        })
+                            pattern: None,
+                            bindings: None,
+                            is_type: is_type_export(export).then_some(true),
+                            magic_comments: None,
+                            glob: None,
+                            meta: None,
+                        });
+                        // export * as a from 'b'
+                        for specifier in &export.specifiers {
+                            if let ast::ExportSpecifier::Namespace(namespace) = specifier {
+                                let name = match &namespace.name {
+                                    ast::ModuleExportName::Ident(ident) => ident.sym.to_string(),
+                                    ast::ModuleExportName::Str(str) => str.value.to_string(),
+                                };
+                                self.add_star_export(
+                                    src,
+                                    export.span,
+                                    Some(name),
+                                    export.type_only,
+                                );
+                            }
+                        }
                     }
                 }
             }
@@ -776,7 +2371,11 @@ impl VisitMut for ImportExportVisitor {
                 }
             }
             // export * from 'vv'
+            // export type * from 'vv'
             ast::ModuleDecl::ExportAll(export) => {
+                if self.link_reexports {
+                    self.parse_star_export(export);
+                }
                 // add import
                 let name = export.src.value.to_string();
                 let (start, end) = self.get_real_span_without_quotes(export.src.span);
@@ -793,25 +2392,128 @@ impl VisitMut for ImportExportVisitor {
                     a,
                     t: ImportType::Static,
                     at: attrs,
+                    pattern: None,
+                    bindings: None,
+                    is_type: (self.include_types && export.type_only).then_some(true),
+                    magic_comments: None,
+                    glob: None,
+                    meta: None,
                 });
+                self.add_star_export(&export.src, export.span, None, export.type_only);
             }
             // export default function a () {}
             ast::ModuleDecl::ExportDefaultDecl(export) => {
                 self.parse_export_default_decl(export);
             }
             // export = a
-            // not support
-            ast::ModuleDecl::TsExportAssignment(_) => {}
+            ast::ModuleDecl::TsExportAssignment(export) => {
+                self.parse_export_assignment(export);
+            }
             // export as namespace a
-            ast::ModuleDecl::TsNamespaceExport(_) => {}
+            ast::ModuleDecl::TsNamespaceExport(export) => {
+                self.parse_namespace_export(export);
+            }
+            // import a = require('b')
+            // export import a = require('b')
             // import TypeScript = TypeScriptServices.TypeScript;
-            ast::ModuleDecl::TsImportEquals(_) => {}
+            ast::ModuleDecl::TsImportEquals(import) => {
+                self.parse_import_equals(import);
+            }
         };
         decl.visit_mut_children_with(self)
     }
 
+    fn visit_mut_ts_import_type(&mut self, node: &mut ast::TsImportType) {
+        if self.type_references {
+            self.parse_type_import(node);
+        }
+        node.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_function(&mut self, node: &mut ast::Function) {
+        self.function_depth += 1;
+        node.visit_mut_children_with(self);
+        self.function_depth -= 1;
+    }
+
+    fn visit_mut_arrow_expr(&mut self, node: &mut ast::ArrowExpr) {
+        self.function_depth += 1;
+        node.visit_mut_children_with(self);
+        self.function_depth -= 1;
+    }
+
+    fn visit_mut_class(&mut self, node: &mut ast::Class) {
+        self.function_depth += 1;
+        node.visit_mut_children_with(self);
+        self.function_depth -= 1;
+    }
+
+    // await a
+    fn visit_mut_await_expr(&mut self, node: &mut ast::AwaitExpr) {
+        let (start, _) = self.get_real_span(node.span);
+        self.detect_top_level_await(start);
+        node.visit_mut_children_with(self);
+    }
+
+    // for await (const a of b) {}
+    fn visit_mut_for_of_stmt(&mut self, node: &mut ast::ForOfStmt) {
+        if node.is_await {
+            let (start, _) = self.get_real_span(node.span);
+            self.detect_top_level_await(start);
+        }
+        node.visit_mut_children_with(self);
+    }
+
+    // await using a = b
+    fn visit_mut_using_decl(&mut self, node: &mut ast::UsingDecl) {
+        if node.is_await {
+            let (start, _) = self.get_real_span(node.span);
+            self.detect_top_level_await(start);
+        }
+        node.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_getter_prop(&mut self, node: &mut ast::GetterProp) {
+        self.function_depth += 1;
+        node.visit_mut_children_with(self);
+        self.function_depth -= 1;
+    }
+
+    fn visit_mut_setter_prop(&mut self, node: &mut ast::SetterProp) {
+        self.function_depth += 1;
+        node.visit_mut_children_with(self);
+        self.function_depth -= 1;
+    }
+
     // dynamic import or import phase
     fn visit_mut_expr(&mut self, node: &mut ast::Expr) {
+        self.detect_cjs_syntax(node);
+        if self.cjs {
+            match node {
+                ast::Expr::Call(call) => {
+                    self.parse_require(call);
+                    self.parse_define_property(call);
+                }
+                ast::Expr::Assign(assign) => self.parse_cjs_assign(assign),
+                _ => {}
+            }
+        }
+        if self.assets {
+            if let ast::Expr::New(new) = node {
+                self.parse_new_expr(new);
+            }
+        }
+        if self.import_meta_members {
+            self.parse_import_meta_member(node);
+        }
+        if self.hmr.is_some() {
+            self.parse_hmr_call(node);
+        }
+        if self.glob_imports {
+            if let ast::Expr::Call(call) = node {
+                self.parse_glob_import(call);
+            }
+        }
         if let ast::Expr::Call(call) = node {
             if let ast::Callee::Import(import) = call.callee {
                 let first_arg = call.args.get(0);
@@ -837,12 +2539,23 @@ impl VisitMut for ImportExportVisitor {
                             }
                         }
                         // import(`abc`)
-                        ast::Expr::Tpl(_tpl) => {
-                            // TODO: actually, we know what is in there. but `es-module-lexer` does not know.
+                        // import('a' + 'b')
+                        // actually, we know what is in there. but `es-module-lexer` does not know.
+                        expr if self.resolve_static_specifier => {
+                            name = get_static_string(expr);
                         }
                         _ => {}
                     }
 
+                    // import(`./locales/${lang}.json`)
+                    let mut pattern = None;
+                    if self.dynamic_import_pattern && name.is_none() {
+                        pattern = self.get_dynamic_import_pattern(&arg.expr);
+                    }
+
+                    // import(/* webpackChunkName: "a" */ './a')
+                    let magic_comments = self.get_magic_comments(&call.args);
+
                     // calc assert
                     let second_arg = call.args.get(1);
                     if let Some(arg) = second_arg {
@@ -881,6 +2594,12 @@ impl VisitMut for ImportExportVisitor {
                             a,
                             t: t.unwrap(),
                             at: attrs,
+                            pattern,
+                            bindings: None,
+                            is_type: None,
+                            magic_comments,
+                            glob: None,
+                            meta: None,
                         });
                     }
                 }
@@ -904,6 +2623,7 @@ impl VisitMut for ImportExportVisitor {
     // import.meta
     fn visit_mut_meta_prop_expr(&mut self, meta: &mut ast::MetaPropExpr) {
         let (start, end) = self.get_real_span(meta.span);
+        let meta_member = self.get_import_meta_member(meta);
         self.add_import(ImportSpecifier {
             n: None,
             s: start,
@@ -914,6 +2634,12 @@ impl VisitMut for ImportExportVisitor {
             a: *NOT,
             t: ImportType::ImportMeta,
             at: None,
+            pattern: None,
+            bindings: None,
+            is_type: None,
+            magic_comments: None,
+            glob: None,
+            meta: meta_member,
         });
         // `import.meta` can only appear in module
         self.set_module_syntax(true);
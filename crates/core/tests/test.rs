@@ -15,9 +15,11 @@ fn test() {
     let parse_opts = ParseOptions {
         filename: filename_str,
         code,
+        ..Default::default()
     };
     let res = parse(IConfig {
         input: vec![parse_opts],
+        ..Default::default()
     });
     println!("{:#?}", res.unwrap().output[0]);
 }
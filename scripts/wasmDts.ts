@@ -10,12 +10,39 @@ const run = async () => {
   const originContent = await fs.readFile(wasmDTS, 'utf-8')
   const newLines: string[] = []
   const lines = originContent.split('\n')
+  // enums are serialized as numbers by `serde_repr` but typed as string unions,
+  // e.g. `export type Lang = "Js" | "Jsx";` -> `1 | 2`, starting from 1
+  const enumTypes = new Map<string, string>()
   lines.forEach((line) => {
-    if (line.startsWith('export type ImportType')) {
+    const enumMatch = line.match(
+      /^export type (\w+) = ("\w+"(?: \| "\w+")*);$/,
+    )
+    if (enumMatch) {
+      const count = enumMatch[2].split(' | ').length
+      const values = Array.from({ length: count }, (_, idx) => idx + 1)
+      enumTypes.set(enumMatch[1], values.join(' | '))
+    }
+  })
+  const enumTypeRegex = new RegExp(`\\b(${[...enumTypes.keys()].join('|')})\\b`)
+  // config fields of `Option<T>` are typed as `T | null`, make them optional
+  const optionalInterfaces = ['IConfig', 'ParseOptions', 'ParserOptions']
+  let currentInterface: string | undefined
+  lines.forEach((line) => {
+    const interfaceMatch = line.match(/^export interface (\w+)/)
+    if (interfaceMatch) {
+      currentInterface = interfaceMatch[1]
+    } else if (line.startsWith('}')) {
+      currentInterface = undefined
+    }
+    if (currentInterface && optionalInterfaces.includes(currentInterface)) {
+      line = line.replace(/^(\s+\w+): (.+) \| null;$/, '$1?: $2;')
+    }
+    const typeMatch = line.match(/^export type (\w+) =/)
+    if (typeMatch && enumTypes.has(typeMatch[1])) {
       // delete
       return
-    } else if (line.includes(`ImportType`)) {
-      line = line.replace('ImportType', [1, 2, 3, 4, 5].join(' | '))
+    } else if (enumTypes.size && enumTypeRegex.test(line)) {
+      line = line.replace(enumTypeRegex, (name) => enumTypes.get(name)!)
       newLines.push(line)
     } else if (line.includes(`at: string[][] | null;`)) {
       line = line.replace(
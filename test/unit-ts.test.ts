@@ -3,15 +3,22 @@ import { readFileSync } from 'fs'
 import { join } from 'path'
 import { getParser } from './equal'
 
-const parseSingleFile = async (code: string) => {
+type Config = Omit<Parameters<ReturnType<typeof getParser>>[0], 'input'>
+
+const parseSingleFile = async (
+  code: string,
+  config: Config = {},
+  filename = FILENAME,
+) => {
   const parse = getParser()
   return parse({
     input: [
       {
-        filename: FILENAME,
+        filename,
         code,
       },
     ],
+    ...config,
   })
 }
 
@@ -114,3 +121,396 @@ export const { a: b } = {}
 })
 
 // TODO: import defer test case
+
+test('collect errors', async () => {
+  const parse = getParser()
+  const { output } = await parse({
+    input: [
+      { filename: 'a.ts', code: `import a from 'a'` },
+      { filename: 'b.ts', code: `import af` },
+    ],
+    collectErrors: true,
+  })
+  expect(output[0].imports.length).toEqual(1)
+  expect(output[0].error).toBeUndefined()
+  expect(output[1].imports.length).toEqual(0)
+  expect(output[1].error?.filename).toEqual('b.ts')
+  expect(output[1].error?.line).toEqual(1)
+})
+
+test('structured diagnostics', async () => {
+  const { output } = await parseSingleFile(`import af`, { collectErrors: true })
+  const diagnostics = output[0].diagnostics!
+  expect(diagnostics.length).toEqual(1)
+  expect(diagnostics[0].severity).toEqual(1)
+  expect(diagnostics[0].s).toEqual(9)
+  expect(diagnostics[0].labels[0].primary).toEqual(true)
+})
+
+test('error recovery', async () => {
+  const code = `
+import a from 'a'
+export const b = 1
+import c fro 'c'
+import d from 'd'
+`
+  const { output } = await parseSingleFile(code, { errorRecovery: true })
+  expect(output[0].imports.length).toEqual(1)
+  expect(output[0].imports[0].n).toEqual('a')
+  expect(output[0].exports.length).toEqual(1)
+  expect(output[0].error?.line).toEqual(4)
+  expect(output[0].diagnostics?.length).toEqual(1)
+})
+
+test('lang override', async () => {
+  const parse = getParser()
+  const code = `import type { A } from 'a'\nimport b from 'b'\nconst x: A = b`
+  const { output } = await parse({
+    input: [
+      // virtual module without extension
+      { filename: '\0virtual:foo', code, lang: 3 },
+      // query and hash suffix
+      { filename: '/src/a.ts?v=123#hash', code },
+    ],
+  })
+  expect(output[0].imports.length).toEqual(1)
+  expect(output[1].imports.length).toEqual(1)
+})
+
+test('parser options', async () => {
+  const parse = getParser()
+  const code = `import a from 'a'\nreturn a`
+  const { output } = await parse({
+    input: [
+      { filename: 'a.js', code },
+      { filename: 'b.js', code, parser: { allowReturnOutsideFunction: false } },
+    ],
+    parser: { allowReturnOutsideFunction: true },
+    collectErrors: true,
+  })
+  expect(output[0].imports.length).toEqual(1)
+  expect(output[1].error).toBeDefined()
+})
+
+test('invalid parser target', async () => {
+  const parse = getParser()
+  const code = `import a from 'a'`
+  const { output } = await parse({
+    input: [
+      { filename: 'a.js', code, parser: { target: 'es3000' } },
+      { filename: 'b.js', code },
+    ],
+    collectErrors: true,
+  })
+  expect(output[0].error?.message).toContain('invalid parser target')
+  expect(output[1].imports.length).toEqual(1)
+})
+
+test('commonjs', async () => {
+  const parse = getParser()
+  const { output } = await parse({
+    input: [
+      {
+        filename: 'index.js',
+        code: `
+const a = require('a')
+const b = require.resolve('b')
+module.exports = { c }
+exports.d = 1
+Object.defineProperty(exports, 'e', { value: 1 })
+`,
+      },
+    ],
+    cjs: true,
+  })
+  const { imports, exports } = output[0]
+  expect(imports.map((i) => [i.n, i.t])).toEqual([
+    ['a', 8],
+    ['b', 9],
+  ])
+  expect(exports.map((e) => [e.n, e.t])).toEqual([
+    ['default', 1],
+    ['c', 1],
+    ['d', 2],
+    ['e', 3],
+  ])
+})
+
+test('module kind', async () => {
+  const parse = getParser()
+  const codes = [
+    `import a from 'a'`,
+    `const a = require('a')`,
+    `import a from 'a'\nmodule.exports = a`,
+    `function f() { require('a') }`,
+  ]
+  const { output } = await parse({
+    input: codes.map((code) => ({ filename: 'index.js', code })),
+  })
+  expect(output.map((o) => o.moduleKind)).toEqual([1, 2, 3, 4])
+})
+
+test('top level await', async () => {
+  const parse = getParser()
+  const { output } = await parse({
+    input: [
+      { filename: 'a.ts', code: `async function f() { await a }\nawait b` },
+      {
+        filename: 'b.ts',
+        code: `const f = async () => { for await (const x of y) {} }`,
+      },
+    ],
+  })
+  expect(output[0].hasTopLevelAwait).toEqual(true)
+  expect(output[0].topLevelAwait).toEqual({ s: 31, e: 36 })
+  expect(output[1].hasTopLevelAwait).toEqual(false)
+  expect(output[1].topLevelAwait).toBeUndefined()
+})
+
+test('resolve static specifier', async () => {
+  const code = "import(`./a.js`); import('./b' + '.js'); import(`./${c}.js`)"
+  const { output } = await parseSingleFile(code, {
+    resolveStaticSpecifier: true,
+  })
+  expect(output[0].imports.map((i) => i.n)).toEqual([
+    './a.js',
+    './b.js',
+    undefined,
+  ])
+})
+
+test('dynamic import pattern', async () => {
+  const code = "import(`./locales/${lang}.json`); import('./a/' + b); import(c)"
+  const { output } = await parseSingleFile(code, { dynamicImportPattern: true })
+  const [locale, concat, variable] = output[0].imports
+  expect(locale.pattern).toEqual({
+    glob: './locales/*.json',
+    parts: ['./locales/', '.json'],
+    placeholders: [{ s: 20, e: 24 }],
+  })
+  expect(concat.pattern!.glob).toEqual('./a/*')
+  expect(variable.pattern).toBeUndefined()
+})
+
+test('import bindings', async () => {
+  const code = `import a, { b as c, type d } from 'x'\nimport * as ns from 'y'`
+  const { output } = await parseSingleFile(code, { importBindings: true })
+  expect(output[0].imports[0].bindings).toEqual([
+    { n: 'default', ln: 'a', t: 1, s: -1, e: -1, ls: 7, le: 8 },
+    { n: 'b', ln: 'c', t: 2, s: 12, e: 13, ls: 17, le: 18 },
+  ])
+  expect(output[0].imports[1].bindings).toEqual([
+    { n: '*', ln: 'ns', t: 3, s: -1, e: -1, ls: 50, le: 52 },
+  ])
+})
+
+test('include types', async () => {
+  const code = `import type { A } from 'a'\nimport b, { type C } from 'c'\nexport { d, type E } from 'e'`
+  const { output } = await parseSingleFile(code, { includeTypes: true })
+  expect(output[0].imports.map((i) => [i.n, i.isType])).toEqual([
+    ['a', true],
+    ['c', false],
+    ['e', false],
+  ])
+  expect(output[0].exports.map((e) => [e.n, e.isType])).toEqual([
+    ['d', false],
+    ['E', true],
+  ])
+})
+
+test('export type star', async () => {
+  const code = `export type * from './a'`
+  const { output } = await parseSingleFile(code)
+  expect(output[0].imports).toEqual([
+    { n: './a', s: 20, e: 23, ss: 0, se: 24, d: -1, a: -1, t: 1 },
+  ])
+
+  const { output: output1 } = await parseSingleFile(code, {
+    includeTypes: true,
+  })
+  expect(output1[0].imports[0].isType).toEqual(true)
+})
+
+test('import equals and export assignment', async () => {
+  const code = `import fs = require('fs')\nexport = fs`
+  const { output } = await parseSingleFile(code)
+  expect(output[0].imports).toMatchObject([
+    { n: 'fs', s: 21, e: 23, ss: 0, se: 25, t: 10 },
+  ])
+  expect(output[0].exports).toMatchObject([
+    { n: 'default', ln: 'fs', s: 33, e: 34, ls: 35, le: 37, t: 4 },
+  ])
+})
+
+test('type references', async () => {
+  const code = `/// <reference types="node" />\ntype A = typeof import('./a')`
+  const { output } = await parseSingleFile(
+    code,
+    { typeReferences: true },
+    'index.d.ts',
+  )
+  expect(output[0].imports).toMatchObject([
+    { n: 'node', s: 22, e: 26, ss: 0, se: 30, t: 13, isType: true },
+    { n: './a', s: 55, e: 58, ss: 47, se: 60, t: 11, isType: true },
+  ])
+})
+
+test('jsdoc imports', async () => {
+  const code = `/** @import { A } from './a' */\n/** @type {import('./b').B} */\nlet b`
+  const { output } = await parseSingleFile(code, { jsdoc: true })
+  expect(output[0].imports).toEqual([])
+  expect(output[0].jsdocImports).toEqual([
+    { n: './a', s: 24, e: 27, ss: 0, se: 31, t: 1 },
+    { n: './b', s: 19, e: 22, ss: 32, se: 62, t: 2 },
+  ])
+
+  const { output: output1 } = await parseSingleFile(
+    `/** @returns {reimport('./a')} */`,
+    { jsdoc: true },
+  )
+  expect(output1[0].jsdocImports).toEqual([])
+})
+
+test('magic comments', async () => {
+  const code = `import(/* webpackChunkName: "a", webpackPrefetch: true */ './a')\nimport(/* @vite-ignore */ b)`
+  const { output } = await parseSingleFile(code, { magicComments: true })
+  expect(output[0].imports[0].magicComments).toEqual([
+    { key: 'webpackChunkName', value: 'a', s: 7, e: 57 },
+    { key: 'webpackPrefetch', value: 'true', s: 7, e: 57 },
+  ])
+  expect(output[0].imports[1].magicComments).toEqual([
+    { key: '@vite-ignore', s: 72, e: 90 },
+  ])
+})
+
+test('assets', async () => {
+  const code = `new URL('./a.wasm', import.meta.url)\nnew Worker(new URL('./b.js', import.meta.url))`
+  const { output } = await parseSingleFile(code, { assets: true })
+  expect(output[0].imports.filter((i) => i.t !== 3)).toMatchObject([
+    { n: './a.wasm', s: 9, e: 17, ss: 0, se: 36, t: 14 },
+    { n: './b.js', s: 57, e: 63, ss: 37, se: 83, t: 15 },
+  ])
+})
+
+test('glob imports', async () => {
+  const code = `import.meta.glob('./pages/*.tsx', { eager: true })\nrequire.context('./dir', true, /\\.ts$/)`
+  const { output } = await parseSingleFile(code, { globImports: true })
+  const [glob, , context] = output[0].imports
+  expect(glob.t).toEqual(16)
+  expect(glob.glob).toEqual({
+    patterns: ['./pages/*.tsx'],
+    options: [['eager', 'true']],
+    os: 34,
+    oe: 49,
+  })
+  expect(context.t).toEqual(17)
+  expect(context.glob).toEqual({
+    patterns: ['./dir'],
+    options: [
+      ['recursive', 'true'],
+      ['regExp', '/\\.ts$/'],
+    ],
+    os: 76,
+    oe: 89,
+  })
+})
+
+test('import meta members', async () => {
+  const code = `import.meta.env.MODE\nimport.meta.hot?.accept()`
+  const { output } = await parseSingleFile(code, { importMetaMembers: true })
+  expect(output[0].imports.map((i) => i.meta)).toEqual([
+    { props: ['env', 'MODE'], s: 0, e: 20, call: false },
+    { props: ['hot', 'accept'], s: 21, e: 44, call: true },
+  ])
+})
+
+test('hmr', async () => {
+  const code = `import.meta.hot.accept()\nimport.meta.hot.accept(['./a'], cb)\nmodule.hot.decline('./b')`
+  const { output } = await parseSingleFile(code, { hmr: true })
+  expect(output[0].hmr).toEqual([
+    { t: 1, deps: [], ss: 0, se: 24 },
+    { t: 1, deps: [{ n: './a', s: 50, e: 53 }], ss: 25, se: 60 },
+    { t: 3, deps: [{ n: './b', s: 81, e: 84 }], ss: 61, se: 86 },
+  ])
+})
+
+test('full destructuring', async () => {
+  const code = `export const { a: { b }, c = 1, ...d } = {}, [e, [f]] = []`
+  const { output } = await parseSingleFile(code, { fullDestructuring: true })
+  expect(output[0].exports.map((e) => e.n)).toEqual(['b', 'c', 'd', 'e', 'f'])
+})
+
+test('export default from', async () => {
+  const code = `export v, { a } from 'mod'`
+  const { output } = await parseSingleFile(code, {}, 'index.js')
+  expect(output[0].imports).toMatchObject([{ n: 'mod', ss: 0, se: 26 }])
+  expect(output[0].exports).toEqual([
+    { n: 'v', ln: 'default', s: 7, e: 8, ls: -1, le: -1 },
+    { n: 'a', ln: 'a', s: 12, e: 13, ls: 12, le: 13 },
+  ])
+})
+
+test('link reexports', async () => {
+  const code = `export const a = 1\nexport { b as c } from './b'\nexport * from './d'`
+  const { output } = await parseSingleFile(code, { linkReexports: true })
+  expect(output[0].exports.map((e) => [e.n, e.t, e.importIndex])).toEqual([
+    ['a', 6, undefined],
+    ['c', 7, 0],
+    ['*', 9, 1],
+  ])
+  expect(output[0].imports.map((i) => i.n)).toEqual(['./b', './d'])
+
+  const { output: output1 } = await parseSingleFile(
+    `export /* * */ * from './a'`,
+    { linkReexports: true },
+  )
+  expect(output1[0].exports).toMatchObject([{ n: '*', s: 15, e: 16 }])
+})
+
+test('star exports', async () => {
+  const code = `export * from './a'\nexport * as b from './b'`
+  const { output } = await parseSingleFile(code)
+  expect(output[0].starExports).toEqual([
+    { n: './a', s: 15, e: 18, ss: 0, se: 19 },
+    { n: './b', s: 40, e: 43, ss: 20, se: 44, namespace: 'b' },
+  ])
+
+  // all lists agree on `export type *`
+  const typeCode = `export type * from './a'`
+  const { output: output1 } = await parseSingleFile(typeCode, {
+    linkReexports: true,
+  })
+  expect(output1[0].imports.map((i) => i.n)).toEqual(['./a'])
+  expect(output1[0].exports).toMatchObject([{ n: '*', t: 9, importIndex: 0 }])
+  expect(output1[0].starExports).toMatchObject([{ n: './a' }])
+  expect(output1[0].starExports[0].isType).toBeUndefined()
+  const { output: output2 } = await parseSingleFile(typeCode, {
+    includeTypes: true,
+    linkReexports: true,
+  })
+  expect(output2[0].imports[0].isType).toEqual(true)
+  expect(output2[0].exports[0].isType).toEqual(true)
+  expect(output2[0].starExports[0].isType).toEqual(true)
+})
+
+test('export kinds', async () => {
+  const code = [
+    'export const a = 1',
+    'export let b',
+    'export async function* c() {}',
+    'export default class {}',
+    'export { a as d }',
+    'let e = 1',
+    'export { e, f }',
+  ].join('\n')
+  const { output } = await parseSingleFile(code, { exportKinds: true })
+  expect(output[0].exports.map((e) => [e.n, e.kind, e.mutable])).toEqual([
+    ['a', 1, false],
+    ['b', 2, true],
+    ['c', 6, false],
+    ['default', 7, false],
+    ['d', 10, false],
+    ['e', 10, true],
+    ['f', 10, undefined],
+  ])
+})